@@ -0,0 +1,139 @@
+//  LIB.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:07:58
+//  Last edited:
+//    15 Oct 2026, 14:07:58
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements derive macros for the auxillary traits in the `justact`
+//!   crate (i.e., `Identifiable`, `Authored`, `Actored` and
+//!   `Affectored`).
+//
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Ident, Index, Member, Type};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Finds the one field in the given struct that has been annotated with the given attribute.
+///
+/// # Arguments
+/// - `input`: The [`DeriveInput`] to search.
+/// - `attr`: The name of the attribute to search for (e.g., `id`).
+///
+/// # Returns
+/// A tuple of the field's [`Member`] (i.e., how to access it) and its [`Type`].
+///
+/// # Errors
+/// This function errors if `input` is not a struct, or if there is not **exactly** one field
+/// annotated with `attr`.
+fn find_field<'i>(input: &'i DeriveInput, attr: &str) -> Result<(Member, &'i Type), syn::Error> {
+    // Only structs are supported
+    let fields = match &input.data {
+        Data::Struct(s) => &s.fields,
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new_spanned(&input.ident, format!("Can only derive with `#[{attr}]` on structs")));
+        },
+    };
+
+    // Find exactly one annotated field
+    let mut res: Option<(Member, &'i Type)> = None;
+    for (i, field) in fields.iter().enumerate() {
+        if !field.attrs.iter().any(|a| a.path().is_ident(attr)) {
+            continue;
+        }
+        if res.is_some() {
+            return Err(syn::Error::new_spanned(field, format!("Only one field may be annotated with `#[{attr}]`")));
+        }
+        let member: Member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+        res = Some((member, &field.ty));
+    }
+    res.ok_or_else(|| syn::Error::new_spanned(&input.ident, format!("Missing a field annotated with `#[{attr}]`")))
+}
+
+/// Generates the implementation for one of the auxillary traits.
+///
+/// # Arguments
+/// - `input`: The [`DeriveInput`] to derive the trait for.
+/// - `attr`: The name of the attribute marking the field to return (e.g., `id`).
+/// - `trait_name`: The name of the trait in `justact::auxillary` to implement (e.g., `Identifiable`).
+/// - `assoc_name`: The name of the trait's associated identifier type (e.g., `Id`).
+/// - `method_name`: The name of the trait's method returning the identifier (e.g., `id`).
+///
+/// # Returns
+/// A [`TokenStream2`] with the implementation.
+///
+/// # Errors
+/// This function errors if we failed to find the field to return.
+fn derive_auxillary(input: &DeriveInput, attr: &str, trait_name: &str, assoc_name: &str, method_name: &str) -> Result<TokenStream2, syn::Error> {
+    let (member, ty): (Member, &Type) = find_field(input, attr)?;
+
+    let name: &Ident = &input.ident;
+    let trait_name = Ident::new(trait_name, Span::call_site());
+    let assoc_name = Ident::new(assoc_name, Span::call_site());
+    let method_name = Ident::new(method_name, Span::call_site());
+    let (impl_gen, ty_gen, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_gen ::justact::auxillary::#trait_name for #name #ty_gen #where_clause {
+            type #assoc_name = #ty;
+
+            #[inline]
+            fn #method_name(&self) -> &Self::#assoc_name { &self.#member }
+        }
+    })
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Derives `justact::auxillary::Identifiable` for a struct.
+///
+/// The field containing the identifier must be annotated with `#[id]`. Its type becomes
+/// `Identifiable::Id`.
+#[proc_macro_derive(Identifiable, attributes(id))]
+pub fn identifiable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_auxillary(&input, "id", "Identifiable", "Id", "id").unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `justact::auxillary::Authored` for a struct.
+///
+/// The field containing the identifier of the author must be annotated with `#[author]`. Its type
+/// becomes `Authored::AuthorId`.
+#[proc_macro_derive(Authored, attributes(author))]
+pub fn authored(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_auxillary(&input, "author", "Authored", "AuthorId", "author_id").unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `justact::auxillary::Actored` for a struct.
+///
+/// The field containing the identifier of the actor must be annotated with `#[actor]`. Its type
+/// becomes `Actored::ActorId`.
+#[proc_macro_derive(Actored, attributes(actor))]
+pub fn actored(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_auxillary(&input, "actor", "Actored", "ActorId", "actor_id").unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `justact::auxillary::Affectored` for a struct.
+///
+/// The field containing the identifier of the affector must be annotated with `#[affector]`. Its
+/// type becomes `Affectored::AffectorId`.
+#[proc_macro_derive(Affectored, attributes(affector))]
+pub fn affectored(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_auxillary(&input, "affector", "Affectored", "AffectorId", "affector_id").unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,87 @@
+//  DERIVE.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:54:14
+//  Last edited:
+//    15 Oct 2026, 14:54:14
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Tests the derive macros for the auxillary traits.
+//
+
+use std::collections::HashMap;
+
+use justact::auxillary::{Actored, Affectored, Authored, Identifiable};
+use justact::collections::map::{InfallibleMap, InfallibleMapSync};
+
+
+/***** HELPERS *****/
+/// Some message-like struct deriving (almost) everything.
+#[derive(Clone, Debug, Identifiable, Authored, Actored, PartialEq)]
+struct Statement {
+    #[id]
+    id:      String,
+    #[author]
+    author:  String,
+    #[actor]
+    actor:   String,
+    payload: &'static str,
+}
+
+/// Some effect-like tuple struct.
+#[derive(Affectored)]
+struct Effect(#[affector] u32, &'static str);
+
+/// Shorthand for creating a [`Statement`].
+fn stmt(id: &str, author: &str, actor: &str, payload: &'static str) -> Statement {
+    Statement { id: id.into(), author: author.into(), actor: actor.into(), payload }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[test]
+fn test_derive_accessors() {
+    let s: Statement = stmt("s1", "amy", "bob", "foo.");
+    assert_eq!(s.id(), "s1");
+    assert_eq!(s.author_id(), "amy");
+    assert_eq!(s.actor_id(), "bob");
+
+    let e: Effect = Effect(42, "bar.");
+    assert_eq!(*e.affector_id(), 42);
+    assert_eq!(e.1, "bar.");
+}
+
+#[test]
+fn test_derive_in_vec_map() {
+    let mut map: Vec<Statement> = Vec::new();
+    assert_eq!(InfallibleMapSync::add(&mut map, stmt("s1", "amy", "amy", "foo.")), None);
+    assert_eq!(InfallibleMapSync::add(&mut map, stmt("s2", "bob", "amy", "bar.")), None);
+    assert_eq!(InfallibleMapSync::add(&mut map, stmt("s1", "cho", "amy", "baz.")), Some(stmt("s1", "amy", "amy", "foo.")));
+    assert_eq!(InfallibleMap::len(&map), 2);
+    assert_eq!(InfallibleMap::get(&map, &"s1".to_string()).map(|s| s.payload), Some("baz."));
+    assert_eq!(InfallibleMap::get(&map, &"s2".to_string()).map(|s| s.author_id().as_str()), Some("bob"));
+    assert_eq!(InfallibleMap::get(&map, &"s3".to_string()), None);
+}
+
+#[test]
+fn test_derive_in_hash_map() {
+    let mut map: HashMap<String, Statement> = HashMap::new();
+    assert_eq!(InfallibleMapSync::add(&mut map, stmt("s1", "amy", "amy", "foo.")), None);
+    assert_eq!(InfallibleMapSync::add(&mut map, stmt("s2", "bob", "amy", "bar.")), None);
+    assert_eq!(InfallibleMap::len(&map), 2);
+    assert_eq!(InfallibleMap::get(&map, &"s2".to_string()).map(|s| s.payload), Some("bar."));
+    assert_eq!(InfallibleMapSync::remove(&mut map, &"s1".to_string()).map(|s| s.payload), Some("foo."));
+    assert_eq!(InfallibleMap::get(&map, &"s1".to_string()), None);
+}
+
+#[test]
+fn test_derive_ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,7 @@
+#[allow(dead_code)]
+#[derive(justact::auxillary::Identifiable)]
+struct Message {
+    author_id: String,
+}
+
+fn main() {}
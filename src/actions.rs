@@ -4,7 +4,7 @@
 //  Created:
 //    11 Dec 2024, 10:06:41
 //  Last edited:
-//    21 Jan 2025, 14:48:00
+//    15 Oct 2026, 15:21:34
 //  Auto updated?
 //    Yes
 //
@@ -12,13 +12,31 @@
 //!   Defines actions, which enact certain effects.
 //
 
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use auto_traits::pointer_impls;
 
-use crate::auxillary::Actored;
-use crate::messages::MessageSet;
+use crate::auxillary::{Actored, Affectored, Authored, Identifiable};
+use crate::collections::map::InfallibleMap;
+use crate::messages::{Message, MessageSet};
+use crate::policies::{Denotation, Extractor, Policy};
+
+
+/***** AUXILLARY *****/
+/// Maps the affectors of a policy to the effects they must carry out.
+///
+/// See [`Action::dispatch_table()`] for more information.
+pub type DispatchTable<P> = HashMap<
+    <<<<P as Policy>::Denotation as Denotation>::Effect as Affectored>::AffectorId as ToOwned>::Owned,
+    Vec<<<P as Policy>::Denotation as Denotation>::Effect>,
+>;
+
+
+
 
 
 /***** LIBRARY *****/
@@ -58,6 +76,84 @@ pub trait Action: Actored {
     /// A [`MessageSet`] encoding the extra statements included by the actor.
     fn payload(&self) -> MessageSet<Self::Message>;
 
+    /// Computes which effects every agent must carry out as a consequence of this action.
+    ///
+    /// This extracts the policy from the action's [payload](Action::payload()), computes its
+    /// denotation and groups the effects in it by their [affector](Affectored::affector_id()).
+    /// This turns a (validated) action into per-agent to-do lists.
+    ///
+    /// Note that this function only supports extractors that produce policy which does not borrow
+    /// from the extracted messages, as the payload is computed on the fly.
+    ///
+    /// # Arguments
+    /// - `extractor`: The [`Extractor`] used to extract the policy from the payload.
+    ///
+    /// # Returns
+    /// A map from affectors to the list of effects that they must carry out.
+    ///
+    /// # Errors
+    /// This function errors if the policy in the payload failed to be extracted.
+    fn dispatch_table<X, P, E>(&self, extractor: &X) -> Result<DispatchTable<P>, E>
+    where
+        Self::Message: Message,
+        <Self::Message as Authored>::AuthorId: ToOwned,
+        X: for<'m> Extractor<<Self::Message as Authored>::AuthorId, <Self::Message as Message>::Payload, Policy<'m> = P, Error<'m> = E>,
+        P: Policy,
+        <P::Denotation as Denotation>::Effect: Clone,
+        <<P::Denotation as Denotation>::Effect as Affectored>::AffectorId: ToOwned,
+        <<<P::Denotation as Denotation>::Effect as Affectored>::AffectorId as ToOwned>::Owned: Eq + Hash,
+    {
+        let payload: MessageSet<Self::Message> = self.payload();
+        let policy: P = extractor.extract(&payload)?;
+        let mut table: DispatchTable<P> = HashMap::new();
+        for effect in policy.truths().iter_effects() {
+            table.entry(effect.affector_id().to_owned()).or_default().push(effect.clone());
+        }
+        Ok(table)
+    }
+
+    /// Checks whether this action produces exactly the given effects.
+    ///
+    /// This extracts the policy from the action's [payload](Action::payload()), computes its
+    /// denotation and compares the effects in it to `expected` by [ID](Identifiable::id()). The
+    /// order of `expected` does not matter. If the denotation contains any effect not in
+    /// `expected`, or if any effect in `expected` is missing from the denotation, the action does
+    /// **not** produce the expected effects.
+    ///
+    /// Note that `expected` is compared as a list, not as a set. Since a denotation contains every
+    /// effect only once, listing the same effect multiple times in `expected` never matches.
+    ///
+    /// Like [`Action::dispatch_table()`], this only supports extractors that produce policy which
+    /// does not borrow from the extracted messages.
+    ///
+    /// # Arguments
+    /// - `extractor`: The [`Extractor`] used to extract the policy from the payload.
+    /// - `expected`: The effects that this action should produce.
+    ///
+    /// # Returns
+    /// True if the action produces exactly the `expected` effects, false otherwise.
+    ///
+    /// # Errors
+    /// This function errors if the policy in the payload failed to be extracted.
+    fn produces_effects<X, P, E>(&self, extractor: &X, expected: &[<P::Denotation as Denotation>::Effect]) -> Result<bool, E>
+    where
+        Self::Message: Message,
+        <Self::Message as Authored>::AuthorId: ToOwned,
+        X: for<'m> Extractor<<Self::Message as Authored>::AuthorId, <Self::Message as Message>::Payload, Policy<'m> = P, Error<'m> = E>,
+        P: Policy,
+    {
+        let payload: MessageSet<Self::Message> = self.payload();
+        let policy: P = extractor.extract(&payload)?;
+        let truths: P::Denotation = policy.truths();
+        if expected.len() != <P::Denotation as InfallibleMap<_>>::len(&truths) {
+            return Ok(false);
+        }
+
+        // Every expected effect must be produced, and only be expected once
+        let mut seen: HashSet<&<<P::Denotation as Denotation>::Effect as Identifiable>::Id> = HashSet::with_capacity(expected.len());
+        Ok(expected.iter().all(|effect| seen.insert(effect.id()) && <P::Denotation as InfallibleMap<_>>::contains_key(&truths, effect.id())))
+    }
+
 
     /// Optional method that will yield a human-friendly identifier for this action.
     ///
@@ -133,3 +229,406 @@ where
         Arc::new(<T as ConstructableAction>::new(actor_id, basis, extra))
     }
 }
+
+
+
+/// Keeps track of which effects have actually been carried out by their affectors.
+///
+/// After an action has been enacted, its effects must still be carried out. This ledger records
+/// every effect as either _pending_ or _realized_, closing the loop between enactment and
+/// execution. It is typically fed from an [`Action::dispatch_table()`].
+///
+/// # Generics
+/// - `I`: The (owned) type of the effects' identifiers.
+/// - `E`: The type of effects tracked.
+#[derive(Clone, Debug)]
+pub struct EffectLedger<I, E> {
+    /// The effects, together with whether they have been realized.
+    effects: HashMap<I, (E, bool)>,
+}
+
+// Constructors
+impl<I, E> Default for EffectLedger<I, E> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<I, E> EffectLedger<I, E> {
+    /// Constructor for the EffectLedger that initializes it as empty.
+    ///
+    /// # Returns
+    /// A new EffectLedger that doesn't track any effects yet.
+    #[inline]
+    pub fn new() -> Self { Self { effects: HashMap::new() } }
+}
+impl<K, I, E> From<HashMap<K, Vec<E>>> for EffectLedger<I, E>
+where
+    E: Identifiable,
+    E::Id: ToOwned<Owned = I>,
+    I: Borrow<E::Id> + Eq + Hash,
+{
+    /// Builds a ledger from a [`DispatchTable`], marking all its effects as pending.
+    #[inline]
+    fn from(value: HashMap<K, Vec<E>>) -> Self {
+        let mut ledger: Self = Self::new();
+        for effect in value.into_values().flatten() {
+            ledger.record(effect);
+        }
+        ledger
+    }
+}
+
+// EffectLedger
+impl<I, E> EffectLedger<I, E>
+where
+    E: Identifiable,
+    E::Id: ToOwned<Owned = I>,
+    I: Borrow<E::Id> + Eq + Hash,
+{
+    /// Starts tracking a new effect as pending.
+    ///
+    /// If an effect with the same identifier was already tracked, it is left untouched (i.e., an
+    /// effect that is already realized stays realized).
+    ///
+    /// # Arguments
+    /// - `effect`: The effect to track.
+    #[inline]
+    pub fn record(&mut self, effect: E) { self.effects.entry(effect.id().to_owned()).or_insert((effect, false)); }
+
+    /// Marks an effect as realized, i.e., carried out by its affector.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the effect that was carried out.
+    ///
+    /// # Returns
+    /// True if the effect was known and still pending, false if it was already realized or isn't
+    /// tracked by this ledger.
+    #[inline]
+    pub fn mark_realized(&mut self, id: &E::Id) -> bool {
+        match self.effects.get_mut(id) {
+            Some((_, realized)) if !*realized => {
+                *realized = true;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Checks whether a particular effect has been realized.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the effect to check.
+    ///
+    /// # Returns
+    /// [`Some(true)`] if the effect was realized, [`Some(false)`] if it's still pending, or
+    /// [`None`] if it isn't tracked by this ledger.
+    #[inline]
+    pub fn is_realized(&self, id: &E::Id) -> Option<bool> { self.effects.get(id).map(|(_, realized)| *realized) }
+
+    /// Returns all effects that are still pending.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the effects that haven't been realized yet, in arbitrary order.
+    #[inline]
+    pub fn pending(&self) -> impl '_ + Iterator<Item = &E> { self.effects.values().filter(|(_, realized)| !*realized).map(|(effect, _)| effect) }
+
+    /// Returns all effects that are still pending for a particular affector.
+    ///
+    /// # Arguments
+    /// - `affector`: The identifier of the affector whose to-do list to return.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the effects that `affector` hasn't realized yet, in arbitrary order.
+    #[inline]
+    pub fn pending_for<'s>(&'s self, affector: &'s E::AffectorId) -> impl 's + Iterator<Item = &'s E>
+    where
+        E: Affectored,
+        E::AffectorId: PartialEq,
+    {
+        self.pending().filter(move |effect| effect.affector_id() == affector)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+    use crate::collections::map::Map;
+    use crate::collections::set::{Set, SetSync};
+    use crate::policies::Effect;
+
+
+    /// Minimal message that is just an author and a textual payload.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct TestMessage {
+        author_id: String,
+        payload:   String,
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+    impl Message for TestMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+
+    /// Minimal action that is just an actor, a basis and extra messages.
+    #[derive(Clone, Debug)]
+    struct TestAction {
+        actor_id: String,
+        basis:    TestMessage,
+        extra:    MessageSet<TestMessage>,
+    }
+    impl TestAction {
+        /// Constructor for a TestAction that justifies itself with one message per payload.
+        fn new(payloads: &[&str]) -> Self {
+            let mut msgs = payloads.iter().map(|payload| TestMessage { author_id: "amy".into(), payload: payload.to_string() });
+            let basis: TestMessage = msgs.next().unwrap();
+            Self { actor_id: "amy".into(), basis, extra: msgs.collect() }
+        }
+    }
+    impl Actored for TestAction {
+        type ActorId = str;
+
+        #[inline]
+        fn actor_id(&self) -> &Self::ActorId { &self.actor_id }
+    }
+    impl Action for TestAction {
+        type Message = TestMessage;
+
+        #[inline]
+        fn basis(&self) -> &Self::Message { &self.basis }
+
+        #[inline]
+        fn extra(&self) -> &MessageSet<Self::Message> { &self.extra }
+
+        #[inline]
+        fn payload(&self) -> MessageSet<Self::Message> {
+            let mut payload: MessageSet<TestMessage> = self.extra.clone();
+            SetSync::add(&mut payload, self.basis.clone()).unwrap();
+            payload
+        }
+    }
+
+
+    /// Minimal effect, identified by the fact it effects.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestEffect {
+        affector_id: String,
+        fact:        String,
+    }
+    impl Affectored for TestEffect {
+        type AffectorId = str;
+
+        #[inline]
+        fn affector_id(&self) -> &Self::AffectorId { &self.affector_id }
+    }
+    impl Identifiable for TestEffect {
+        type Id = String;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.fact }
+    }
+    impl Effect for TestEffect {
+        type Fact = String;
+
+        #[inline]
+        fn fact(&self) -> &Self::Fact { &self.fact }
+    }
+
+    /// Shorthand for creating a [`TestEffect`].
+    fn eff(affector_id: &str, fact: &str) -> TestEffect { TestEffect { affector_id: affector_id.into(), fact: fact.into() } }
+
+    /// Minimal denotation that consists only of effects, each of which is also true.
+    #[derive(Clone, Debug, Default)]
+    struct TestDenotation {
+        truths:  Vec<String>,
+        effects: Vec<TestEffect>,
+    }
+    impl Set<String> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &String) -> Result<Option<&String>, Self::Error> { Ok(<[String]>::iter(&self.truths).find(|f| *f == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s String>, Self::Error>
+        where
+            String: 's,
+        {
+            Ok(<[String]>::iter(&self.truths))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.truths.len()) }
+    }
+    impl Map<TestEffect> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, id: &String) -> Result<Option<&TestEffect>, Self::Error> { Ok(<[TestEffect]>::iter(&self.effects).find(|e| &e.fact == id)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s TestEffect>, Self::Error>
+        where
+            TestEffect: 's,
+        {
+            Ok(<[TestEffect]>::iter(&self.effects))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.effects.len()) }
+    }
+    impl Denotation for TestDenotation {
+        type Effect = TestEffect;
+        type Fact = String;
+
+        #[inline]
+        fn truth_of(&self, fact: &Self::Fact) -> Option<bool> { Some(<[String]>::contains(&self.truths, fact)) }
+    }
+
+    /// Minimal policy that is a list of effects.
+    #[derive(Clone, Debug, Default)]
+    struct TestPolicy {
+        effects: Vec<TestEffect>,
+    }
+    impl Policy for TestPolicy {
+        type Denotation = TestDenotation;
+
+        #[inline]
+        fn is_valid(&self) -> bool { true }
+
+        #[inline]
+        fn truths(&self) -> Self::Denotation {
+            // Every effect occurs only once in the denotation, even if it's stated multiple times
+            let mut den: TestDenotation = TestDenotation::default();
+            for effect in &self.effects {
+                if !<[String]>::contains(&den.truths, &effect.fact) {
+                    den.truths.push(effect.fact.clone());
+                    den.effects.push(effect.clone());
+                }
+            }
+            den
+        }
+
+        #[inline]
+        fn compose(&self, other: Self) -> Self {
+            let mut res: Self = self.clone();
+            res.compose_mut(other);
+            res
+        }
+
+        #[inline]
+        fn compose_mut(&mut self, other: Self) { self.effects.extend(other.effects); }
+    }
+
+    /// Extractor that reads every message payload as a whitespace-separated list of
+    /// `<affector>:<fact>` effects.
+    struct TestExtractor;
+    impl Extractor<str, str> for TestExtractor {
+        type Policy<'m> = TestPolicy;
+        type Error<'m> = Infallible;
+
+        #[inline]
+        fn extract<'m, M: Message<AuthorId = str, Payload = str>>(&self, msgs: &'m impl Set<M>) -> Result<Self::Policy<'m>, Self::Error<'m>> {
+            let mut policy: TestPolicy = TestPolicy::default();
+            for msg in msgs.iter().unwrap() {
+                for effect in msg.payload().split_whitespace() {
+                    let (affector_id, fact) = effect.split_once(':').unwrap();
+                    policy.effects.push(eff(affector_id, fact));
+                }
+            }
+            Ok(policy)
+        }
+    }
+
+
+    #[test]
+    fn test_dispatch_table() {
+        let action: TestAction = TestAction::new(&["amy:a bob:b", "amy:c"]);
+        let mut table: DispatchTable<TestPolicy> = action.dispatch_table(&TestExtractor).unwrap();
+        assert_eq!(table.len(), 2);
+
+        // The order of effects within every list is arbitrary
+        let mut amy: Vec<TestEffect> = table.remove("amy").unwrap();
+        amy.sort_by(|lhs, rhs| lhs.fact.cmp(&rhs.fact));
+        assert_eq!(amy, vec![eff("amy", "a"), eff("amy", "c")]);
+        assert_eq!(table.remove("bob").unwrap(), vec![eff("bob", "b")]);
+    }
+
+    #[test]
+    fn test_effect_ledger() {
+        let mut ledger: EffectLedger<String, TestEffect> = EffectLedger::new();
+        ledger.record(eff("amy", "a"));
+        ledger.record(eff("amy", "b"));
+        ledger.record(eff("bob", "c"));
+        assert_eq!(ledger.is_realized(&"a".into()), Some(false));
+
+        // Realizing one effect leaves the other pending
+        assert!(ledger.mark_realized(&"a".into()));
+        assert_eq!(ledger.is_realized(&"a".into()), Some(true));
+        assert_eq!(ledger.pending_for("amy").collect::<Vec<&TestEffect>>(), vec![&eff("amy", "b")]);
+        assert_eq!(ledger.pending_for("bob").collect::<Vec<&TestEffect>>(), vec![&eff("bob", "c")]);
+        assert_eq!(ledger.pending().count(), 2);
+
+        // Effects can only be realized once, and unknown ones not at all
+        assert!(!ledger.mark_realized(&"a".into()));
+        assert!(!ledger.mark_realized(&"z".into()));
+        assert_eq!(ledger.is_realized(&"z".into()), None);
+
+        // Recording a realized effect again doesn't reset it
+        ledger.record(eff("amy", "a"));
+        assert_eq!(ledger.is_realized(&"a".into()), Some(true));
+    }
+
+    #[test]
+    fn test_effect_ledger_from_dispatch_table() {
+        let action: TestAction = TestAction::new(&["amy:a bob:b", "amy:c"]);
+        let ledger: EffectLedger<String, TestEffect> = action.dispatch_table(&TestExtractor).unwrap().into();
+        assert_eq!(ledger.pending().count(), 3);
+        assert_eq!(ledger.pending_for("bob").collect::<Vec<&TestEffect>>(), vec![&eff("bob", "b")]);
+    }
+
+    #[test]
+    fn test_produces_effects() {
+        let action: TestAction = TestAction::new(&["amy:a bob:b", "cho:c"]);
+
+        // Exact match, in any order
+        assert!(action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("bob", "b"), eff("cho", "c")]).unwrap());
+        assert!(action.produces_effects(&TestExtractor, &[eff("cho", "c"), eff("amy", "a"), eff("bob", "b")]).unwrap());
+
+        // Missing an expected effect
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("bob", "b"), eff("cho", "c"), eff("dan", "d")]).unwrap());
+        // Denotation produces an effect that is not expected
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("bob", "b")]).unwrap());
+        // Same number of effects, but not the same ones
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("bob", "b"), eff("dan", "d")]).unwrap());
+    }
+
+    #[test]
+    fn test_produces_effects_duplicates() {
+        // Expecting the same effect twice never matches, since the denotation holds it only once
+        let action: TestAction = TestAction::new(&["amy:a"]);
+        assert!(action.produces_effects(&TestExtractor, &[eff("amy", "a")]).unwrap());
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("amy", "a")]).unwrap());
+
+        // Not even if the denotation has as many effects as expected
+        let action: TestAction = TestAction::new(&["amy:a bob:b"]);
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("amy", "a")]).unwrap());
+
+        // Stating an effect twice still only produces it once
+        let action: TestAction = TestAction::new(&["amy:a", "amy:a amy:a"]);
+        assert!(action.produces_effects(&TestExtractor, &[eff("amy", "a")]).unwrap());
+        assert!(!action.produces_effects(&TestExtractor, &[eff("amy", "a"), eff("amy", "a")]).unwrap());
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    10 Dec 2024, 11:00:07
 //  Last edited:
-//    29 Jan 2025, 22:03:45
+//    15 Oct 2026, 15:16:32
 //  Auto updated?
 //    Yes
 //
@@ -15,8 +15,11 @@
 //!   synchronized- and asynchronized sets and write to synchronized sets.
 //
 
+use std::borrow::Borrow;
+use std::collections::HashSet;
 use std::error;
 use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::hash::Hash;
 use std::task::Poll;
 
 use auto_traits::pointer_impls;
@@ -24,6 +27,7 @@ use auto_traits::pointer_impls;
 use crate::actions::ConstructableAction;
 use crate::auxillary::{Actored, Authored, Identifiable};
 use crate::collections::Recipient;
+use crate::collections::counting::{Counting, GossipStats};
 use crate::collections::set::{Set, SetAsync, SetSync};
 use crate::messages::ConstructableMessage;
 
@@ -61,6 +65,33 @@ impl<EA: error::Error, ES: error::Error, EE: error::Error> error::Error for OneO
     }
 }
 
+/// Defines errors that originate from composed agents (see [`SeqAgent`] and [`ParAgent`]).
+#[derive(Debug)]
+pub enum ComposedError<E1, E2> {
+    /// The first agent failed.
+    First(E1),
+    /// The second agent failed.
+    Second(E2),
+}
+impl<E1: Display, E2: Display> Display for ComposedError<E1, E2> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::First(err) => err.fmt(f),
+            Self::Second(err) => err.fmt(f),
+        }
+    }
+}
+impl<E1: error::Error, E2: error::Error> error::Error for ComposedError<E1, E2> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn 'static + error::Error)> {
+        match self {
+            Self::First(err) => err.source(),
+            Self::Second(err) => err.source(),
+        }
+    }
+}
+
 /// Defines errors that originate from the [`View`].
 #[derive(Debug)]
 pub enum Error<I, E> {
@@ -115,6 +146,91 @@ pub struct View<I: ?Sized + ToOwned, A, S, E> {
 }
 
 impl<I: ?Sized + ToOwned, A, S, E> View<I, A, S, E> {
+    /// Creates an isolated, speculative copy of this view.
+    ///
+    /// This is useful for agents exploring hypotheticals, e.g., "if I stated this message, would
+    /// my action be justified?". The fork is a deep copy of all three sets, so it can be mutated
+    /// freely and discarded afterwards. Changes made to a fork **never** propagate back to this
+    /// view (or to anyone else).
+    ///
+    /// Note that "deep" is up to the [`Clone`]-implementations of the sets. Sets that share their
+    /// contents (e.g., through an [`Arc`](std::sync::Arc)) should not be forked this way.
+    ///
+    /// # Returns
+    /// A new [`View`] with copies of this view's sets.
+    #[inline]
+    pub fn fork(&self) -> View<I, A, S, E>
+    where
+        I::Owned: Clone,
+        A: Clone,
+        S: Clone,
+        E: Clone,
+    {
+        View { id: self.id.clone(), agreed: self.agreed.clone(), stated: self.stated.clone(), enacted: self.enacted.clone() }
+    }
+
+    /// Returns a read-only projection of this view.
+    ///
+    /// This is useful for passing the view to sub-routines that should only query it. As the
+    /// [`ReadView`] only borrows the sets immutably, it's enforced at the type level that these
+    /// cannot state, enact, agree or gossip.
+    ///
+    /// # Returns
+    /// A [`ReadView`] borrowing this view.
+    #[inline]
+    pub const fn as_read(&self) -> ReadView<'_, I, A, S, E> {
+        ReadView { id: &self.id, agreed: &self.agreed, stated: &self.stated, enacted: &self.enacted }
+    }
+
+    /// Returns how much this agent has stated and gossiped.
+    ///
+    /// This is only available if the statements are wrapped in a [`Counting`] set, which keeps
+    /// track of the messages sent through it. Since the statistics live in the set, they persist
+    /// across the views handed out for every poll.
+    ///
+    /// Note that both [gossiped](View::gossip()) and [stated](View::state()) messages are counted.
+    /// The latter are recorded as sent to [`Recipient::One`] of this agent.
+    ///
+    /// # Returns
+    /// A reference to the [`GossipStats`] accumulated by the statements.
+    #[inline]
+    pub fn stats<'s, T: 's>(&'s self) -> &'s GossipStats<I::Owned>
+    where
+        S: Borrow<Counting<T, I>>,
+    {
+        self.stated.borrow().stats()
+    }
+
+    /// Returns the agents that have stated at least one message in this view.
+    ///
+    /// Every agent is yielded only once, in the order in which their first message is found in
+    /// the statements.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the (distinct) authors of the stated messages.
+    ///
+    /// # Errors
+    /// This function errors if we failed to iterate over the stated messages.
+    #[inline]
+    pub fn active_agents<'s, MS>(&'s self) -> Result<impl 's + Iterator<Item = &'s MS::AuthorId>, S::Error>
+    where
+        S: Set<MS>,
+        MS: 's + Authored,
+        MS::AuthorId: Eq + Hash,
+    {
+        let mut seen: HashSet<&'s MS::AuthorId> = HashSet::new();
+        let mut agents: Vec<&'s MS::AuthorId> = Vec::new();
+        for msg in self.stated.iter()? {
+            let author_id: &'s MS::AuthorId = msg.author_id();
+            if seen.insert(author_id) {
+                agents.push(author_id);
+            }
+        }
+        Ok(agents.into_iter())
+    }
+
+
+
     /// Have the agent state a message to their own view.
     ///
     /// # Arguments
@@ -169,21 +285,31 @@ impl<I: ?Sized + ToOwned, A, S, E> View<I, A, S, E> {
     ///
     /// Specifically, replaces all of the agreements with the given list.
     ///
+    /// The new agreements are added [atomically](SetSync::add_all_atomic()). Hence, if any of
+    /// them fails to be added, the previous agreements are restored instead of leaving the list
+    /// with only some of the new ones.
+    ///
     /// # Arguments
     /// - `agrees`: An iterator yielding agreements to put in the list.
     ///
     /// # Errors
-    /// This function errors if we failed to clear the existing list or if we added any of the
-    /// agreements yielded by `agrees`.
+    /// This function errors if we failed to copy or clear the existing list or if we failed to
+    /// add any of the agreements yielded by `agrees`.
     #[inline]
     pub fn agree<MS>(&mut self, agrees: impl IntoIterator<Item = MS>) -> Result<(), Error<I::Owned, A::Error>>
     where
         A: SetSync<MS>,
+        MS: Clone,
     {
+        // Keep the old agreements around in case the new ones fail
+        let old: Vec<MS> = self.agreed.iter().map_err(Error::Set)?.cloned().collect();
+
         // Reset, then write the new agreements
         self.agreed.clear().map_err(Error::Set)?;
-        for agree in agrees {
-            self.agreed.add(agree).map_err(Error::Set)?;
+        if let Err((err, _)) = self.agreed.add_all_atomic(agrees) {
+            // NOTE: This is best-effort; the original error is more interesting than this one
+            let _ = self.agreed.add_all_atomic(old);
+            return Err(Error::Set(err));
         }
         Ok(())
     }
@@ -228,6 +354,89 @@ impl<I: ?Sized + ToOwned, A, S, E> View<I, A, S, E> {
 
 
 
+/// Defines a read-only projection of a [`View`].
+///
+/// Obtained through [`View::as_read()`]. Unlike a [`View`], this only exposes its sets
+/// immutably, so only their query methods (e.g., [`Set::iter()`]) are available.
+///
+/// # Examples
+/// Stating a message through a ReadView does not compile:
+/// ```compile_fail,E0599
+/// # use justact::actors::View;
+/// # let view: View<str, (), (), ()> = View { id: "amy".into(), agreed: (), stated: (), enacted: () };
+/// view.as_read().state(());
+/// ```
+/// Neither does enacting an action,
+/// ```compile_fail,E0599
+/// # use justact::actors::View;
+/// # let view: View<str, (), (), ()> = View { id: "amy".into(), agreed: (), stated: (), enacted: () };
+/// view.as_read().enact(());
+/// ```
+/// agreeing on new agreements,
+/// ```compile_fail,E0599
+/// # use justact::actors::View;
+/// # let view: View<str, (), (), ()> = View { id: "amy".into(), agreed: (), stated: (), enacted: () };
+/// view.as_read().agree([()]);
+/// ```
+/// or gossiping a message:
+/// ```compile_fail,E0599
+/// # use justact::actors::View;
+/// # use justact::collections::Recipient;
+/// # let view: View<str, (), (), ()> = View { id: "amy".into(), agreed: (), stated: (), enacted: () };
+/// view.as_read().gossip(Recipient::All, ());
+/// ```
+#[derive(Debug)]
+pub struct ReadView<'v, I: ?Sized + ToOwned, A, S, E> {
+    /// The identifier of the agent for who this view is.
+    id:      &'v I::Owned,
+    /// The set of agreements that have been formulated.
+    agreed:  &'v A,
+    /// The set of messages that have been stated (and visible to this agent!).
+    stated:  &'v S,
+    /// The set of actions that have been enacted (and visible to this agent!).
+    enacted: &'v E,
+}
+
+// Ops
+impl<'v, I: ?Sized + ToOwned, A, S, E> Clone for ReadView<'v, I, A, S, E> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+impl<'v, I: ?Sized + ToOwned, A, S, E> Copy for ReadView<'v, I, A, S, E> {}
+
+// Read-only access
+impl<'v, I: ?Sized + ToOwned, A, S, E> ReadView<'v, I, A, S, E> {
+    /// Returns the identifier of the agent for who this view is.
+    ///
+    /// # Returns
+    /// A reference to the agent's `I`dentifier.
+    #[inline]
+    pub fn id(&self) -> &'v I { self.id.borrow() }
+
+    /// Returns the set of agreements that have been formulated.
+    ///
+    /// # Returns
+    /// A reference to the internal `A`greements-set.
+    #[inline]
+    pub const fn agreed(&self) -> &'v A { self.agreed }
+
+    /// Returns the set of messages that have been stated (and visible to this agent!).
+    ///
+    /// # Returns
+    /// A reference to the internal `S`tatements-set.
+    #[inline]
+    pub const fn stated(&self) -> &'v S { self.stated }
+
+    /// Returns the set of actions that have been enacted (and visible to this agent!).
+    ///
+    /// # Returns
+    /// A reference to the internal `E`nactments-set.
+    #[inline]
+    pub const fn enacted(&self) -> &'v E { self.enacted }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -238,10 +447,13 @@ impl<I: ?Sized + ToOwned, A, S, E> View<I, A, S, E> {
 ///
 /// # Generics
 /// - `MP`: The type of the message payloads supported by this Synchronizer.
+/// - `C`: Some shared, read-only context given to the agent on every poll (e.g., a registry of
+///   [`Extractor`](crate::policies::Extractor)s). Defaults to `()` for agents that don't need any.
 #[pointer_impls(T = U)]
-pub trait Agent<MP>: Identifiable
+pub trait Agent<MP, C = ()>: Identifiable
 where
     MP: ?Sized + ToOwned,
+    C: ?Sized,
     Self::Id: ToOwned,
 {
     /// Any errors that this agent can throw during its execution.
@@ -267,18 +479,54 @@ where
     /// - `view`: A runtime [`View`] that represents this agent's view on the current system.
     ///   How partial this view is, and how much is hidden behind the scenes, depends on the
     ///   simulation.
+    /// - `context`: Some shared context `C` that is not expressible through the sets in the view.
     ///
     /// # Returns
     /// A [`Poll`] which, can either:
     /// - be [`Poll::Ready`], indicating the agent has no more work to do (and can be deleted); or
     /// - a [`Poll::Pending`], indicating the agent wants to stick around.
-    fn poll<A, S, E, SM, SA>(&mut self, view: View<Self::Id, A, S, E>) -> Result<Poll<()>, Self::Error>
+    fn poll<A, S, E, SM, SA>(&mut self, view: View<Self::Id, A, S, E>, context: &C) -> Result<Poll<()>, Self::Error>
     where
         A: Set<SM>,
         S: SetAsync<Self::Id, SM>,
         E: SetAsync<Self::Id, SA>,
         SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
         SA: ConstructableAction<ActorId = Self::Id, Message = SM>;
+
+    /// Gives this agent a final chance to act before it is stopped.
+    ///
+    /// This allows agents to flush any state they kept, e.g., by stating a final message. It is
+    /// called exactly once per agent, after the agent has been polled for the last time; i.e.,
+    /// either when it returned [`Poll::Ready`], or when the [`System`](crate::runtime::System)
+    /// stops for any other reason (see [`System::run()`](crate::runtime::System::run())).
+    ///
+    /// By default, this does nothing.
+    ///
+    /// # Generics
+    /// - `A`: The globally synchronized set of agreements.
+    /// - `S`: The local view on stated messages.
+    /// - `E`: The local view on enacted actions.
+    /// - `SM`: The type of messages carried within the sets above.
+    /// - `SA`: The type of actions carried within the sets above.
+    ///
+    /// # Arguments
+    /// - `view`: A runtime [`View`] that represents this agent's final view on the system.
+    /// - `context`: Some shared context `C` that is not expressible through the sets in the view.
+    ///
+    /// # Errors
+    /// This function may error if the agent failed to clean up.
+    #[inline]
+    fn on_shutdown<A, S, E, SM, SA>(&mut self, view: View<Self::Id, A, S, E>, context: &C) -> Result<(), Self::Error>
+    where
+        A: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        let _ = (view, context);
+        Ok(())
+    }
 }
 
 
@@ -333,3 +581,1070 @@ where
         SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
         SA: ConstructableAction<ActorId = Self::Id, Message = SM>;
 }
+
+
+
+/// Wraps an [`Agent`] such that it is re-polled when it fails with a transient error.
+///
+/// Errors are classified as transient or fatal by a user-supplied function. Fatal errors are
+/// returned immediately. Transient errors (e.g., a flaky network registry) cause the agent to be
+/// polled again with the same view, up to a maximum number of retries. If it still fails after
+/// that, the last error is returned.
+///
+/// Note that every retry sees the changes made to the view by the failed attempts before it.
+///
+/// # Generics
+/// - `A`: The wrapped [`Agent`].
+/// - `F`: The function classifying errors as transient (`true`) or fatal (`false`).
+#[derive(Clone, Debug)]
+pub struct RetryAgent<A, F> {
+    /// The wrapped agent.
+    agent:        A,
+    /// The maximum number of times an agent is re-polled after a transient error.
+    retries:      usize,
+    /// Decides whether an error is transient.
+    is_transient: F,
+}
+
+// Constructors
+impl<A, F> RetryAgent<A, F> {
+    /// Constructor for the RetryAgent.
+    ///
+    /// # Arguments
+    /// - `agent`: The [`Agent`] to wrap.
+    /// - `retries`: The maximum number of times the agent is re-polled after a transient error.
+    ///   Hence, it is polled at most `1 + retries` times per poll of the wrapper.
+    /// - `is_transient`: Some function that returns true if the given error is transient, or
+    ///   false if it is fatal.
+    ///
+    /// # Returns
+    /// A new RetryAgent that wraps `agent`.
+    #[inline]
+    pub const fn new(agent: A, retries: usize, is_transient: F) -> Self { Self { agent, retries, is_transient } }
+}
+
+// RetryAgent
+impl<A, F> RetryAgent<A, F> {
+    /// Returns the wrapped agent.
+    ///
+    /// # Returns
+    /// A reference to the internal `A`.
+    #[inline]
+    pub const fn inner(&self) -> &A { &self.agent }
+
+    /// Returns the wrapped agent, discarding the retry logic.
+    ///
+    /// # Returns
+    /// The internal `A`.
+    #[inline]
+    pub fn into_inner(self) -> A { self.agent }
+}
+
+// Actors
+impl<A: Identifiable, F> Identifiable for RetryAgent<A, F> {
+    type Id = A::Id;
+
+    #[inline]
+    fn id(&self) -> &Self::Id { self.agent.id() }
+}
+impl<MP, C, A, F> Agent<MP, C> for RetryAgent<A, F>
+where
+    MP: ?Sized + ToOwned,
+    C: ?Sized,
+    A: Agent<MP, C>,
+    A::Id: ToOwned,
+    F: Fn(&A::Error) -> bool,
+{
+    type Error = A::Error;
+
+    #[inline]
+    fn poll<AS, S, E, SM, SA>(&mut self, mut view: View<Self::Id, AS, S, E>, context: &C) -> Result<Poll<()>, Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        let mut attempt: usize = 0;
+        loop {
+            // Re-borrow the view such that we can use it again on failure
+            let reborrow: View<Self::Id, &AS, &mut S, &mut E> =
+                View { id: view.id.borrow().to_owned(), agreed: &view.agreed, stated: &mut view.stated, enacted: &mut view.enacted };
+            match self.agent.poll(reborrow, context) {
+                Ok(res) => return Ok(res),
+                Err(err) if attempt < self.retries && (self.is_transient)(&err) => attempt += 1,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    #[inline]
+    fn on_shutdown<AS, S, E, SM, SA>(&mut self, view: View<Self::Id, AS, S, E>, context: &C) -> Result<(), Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        self.agent.on_shutdown(view, context)
+    }
+}
+
+
+
+/// Composes two [`Agent`]s such that they run one after the other.
+///
+/// The first agent is polled until it returns [`Poll::Ready`]. From then on, the second agent is
+/// polled instead, and the composition is [`Poll::Ready`] when the second agent is. Both agents
+/// act under the identity of the first agent; i.e., they are given the same [`View`].
+///
+/// Errors are propagated as-is (wrapped in a [`ComposedError`]).
+///
+/// # Generics
+/// - `A`: The agent to run first.
+/// - `B`: The agent to run second.
+#[derive(Clone, Debug)]
+pub struct SeqAgent<A, B> {
+    /// The agent to run first.
+    first:      A,
+    /// The agent to run second.
+    second:     B,
+    /// Whether the first agent is done.
+    first_done: bool,
+}
+
+// Constructors
+impl<A, B> SeqAgent<A, B> {
+    /// Constructor for the SeqAgent.
+    ///
+    /// # Arguments
+    /// - `first`: The [`Agent`] to run first.
+    /// - `second`: The [`Agent`] to run once `first` is done.
+    ///
+    /// # Returns
+    /// A new SeqAgent that runs `first`, then `second`.
+    #[inline]
+    pub const fn new(first: A, second: B) -> Self { Self { first, second, first_done: false } }
+}
+
+// Actors
+impl<A: Identifiable, B> Identifiable for SeqAgent<A, B> {
+    type Id = A::Id;
+
+    #[inline]
+    fn id(&self) -> &Self::Id { self.first.id() }
+}
+impl<MP, C, A, B> Agent<MP, C> for SeqAgent<A, B>
+where
+    MP: ?Sized + ToOwned,
+    C: ?Sized,
+    A: Agent<MP, C>,
+    A::Id: ToOwned,
+    B: Agent<MP, C, Id = A::Id>,
+{
+    type Error = ComposedError<A::Error, B::Error>;
+
+    #[inline]
+    fn poll<AS, S, E, SM, SA>(&mut self, mut view: View<Self::Id, AS, S, E>, context: &C) -> Result<Poll<()>, Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        if !self.first_done {
+            // Re-borrow the view such that we can still give it to the second agent in case the first one is done
+            let reborrow: View<Self::Id, &AS, &mut S, &mut E> =
+                View { id: view.id.borrow().to_owned(), agreed: &view.agreed, stated: &mut view.stated, enacted: &mut view.enacted };
+            match self.first.poll(reborrow, context).map_err(ComposedError::First)? {
+                Poll::Ready(()) => self.first_done = true,
+                Poll::Pending => return Ok(Poll::Pending),
+            }
+        }
+        self.second.poll(view, context).map_err(ComposedError::Second)
+    }
+
+    /// Calls the hook of the first agent, then that of the second.
+    #[inline]
+    fn on_shutdown<AS, S, E, SM, SA>(&mut self, mut view: View<Self::Id, AS, S, E>, context: &C) -> Result<(), Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        let reborrow: View<Self::Id, &AS, &mut S, &mut E> =
+            View { id: view.id.borrow().to_owned(), agreed: &view.agreed, stated: &mut view.stated, enacted: &mut view.enacted };
+        self.first.on_shutdown(reborrow, context).map_err(ComposedError::First)?;
+        self.second.on_shutdown(view, context).map_err(ComposedError::Second)
+    }
+}
+
+
+
+/// Composes two [`Agent`]s such that they run side-by-side.
+///
+/// Every poll, both agents are polled (first `A`, then `B`) with the same [`View`]. The
+/// composition is [`Poll::Ready`] when both agents are. Agents that are done are not polled
+/// anymore. Both agents act under the identity of the first agent.
+///
+/// If an agent fails, the error is returned immediately. In particular, if `A` fails, `B` is not
+/// polled that round (i.e., the first error wins).
+///
+/// # Generics
+/// - `A`: The first agent to run.
+/// - `B`: The second agent to run.
+#[derive(Clone, Debug)]
+pub struct ParAgent<A, B> {
+    /// The first agent to run.
+    first:       A,
+    /// The second agent to run.
+    second:      B,
+    /// Whether the first agent is done.
+    first_done:  bool,
+    /// Whether the second agent is done.
+    second_done: bool,
+}
+
+// Constructors
+impl<A, B> ParAgent<A, B> {
+    /// Constructor for the ParAgent.
+    ///
+    /// # Arguments
+    /// - `first`: The first [`Agent`] to run.
+    /// - `second`: The second [`Agent`] to run.
+    ///
+    /// # Returns
+    /// A new ParAgent that runs `first` and `second` side-by-side.
+    #[inline]
+    pub const fn new(first: A, second: B) -> Self { Self { first, second, first_done: false, second_done: false } }
+}
+
+// Actors
+impl<A: Identifiable, B> Identifiable for ParAgent<A, B> {
+    type Id = A::Id;
+
+    #[inline]
+    fn id(&self) -> &Self::Id { self.first.id() }
+}
+impl<MP, C, A, B> Agent<MP, C> for ParAgent<A, B>
+where
+    MP: ?Sized + ToOwned,
+    C: ?Sized,
+    A: Agent<MP, C>,
+    A::Id: ToOwned,
+    B: Agent<MP, C, Id = A::Id>,
+{
+    type Error = ComposedError<A::Error, B::Error>;
+
+    #[inline]
+    fn poll<AS, S, E, SM, SA>(&mut self, mut view: View<Self::Id, AS, S, E>, context: &C) -> Result<Poll<()>, Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        if !self.first_done {
+            // Re-borrow the view such that we can still give it to the second agent
+            let reborrow: View<Self::Id, &AS, &mut S, &mut E> =
+                View { id: view.id.borrow().to_owned(), agreed: &view.agreed, stated: &mut view.stated, enacted: &mut view.enacted };
+            self.first_done = self.first.poll(reborrow, context).map_err(ComposedError::First)?.is_ready();
+        }
+        if !self.second_done {
+            self.second_done = self.second.poll(view, context).map_err(ComposedError::Second)?.is_ready();
+        }
+        Ok(if self.first_done && self.second_done { Poll::Ready(()) } else { Poll::Pending })
+    }
+
+    /// Calls the hook of the first agent, then that of the second.
+    #[inline]
+    fn on_shutdown<AS, S, E, SM, SA>(&mut self, mut view: View<Self::Id, AS, S, E>, context: &C) -> Result<(), Self::Error>
+    where
+        AS: Set<SM>,
+        S: SetAsync<Self::Id, SM>,
+        E: SetAsync<Self::Id, SA>,
+        SM: ConstructableMessage<AuthorId = Self::Id, Payload = MP>,
+        SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        let reborrow: View<Self::Id, &AS, &mut S, &mut E> =
+            View { id: view.id.borrow().to_owned(), agreed: &view.agreed, stated: &mut view.stated, enacted: &mut view.enacted };
+        self.first.on_shutdown(reborrow, context).map_err(ComposedError::First)?;
+        self.second.on_shutdown(view, context).map_err(ComposedError::Second)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+    use crate::actions::Action;
+    use crate::auxillary::Affectored;
+    use crate::collections::map::Map;
+    use crate::messages::{Message, MessageSet};
+    use crate::policies::{Denotation, Effect, Extractor, ExtractorRegistry, Policy, PolicyFormat};
+
+
+    /// Error thrown by the [`FailingSet`].
+    #[derive(Debug)]
+    struct FailError;
+    impl Display for FailError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Refusing to add element") }
+    }
+    impl error::Error for FailError {}
+
+    /// A [`Vec`]-backed set that refuses to add one particular element.
+    #[derive(Debug)]
+    struct FailingSet {
+        elems:   Vec<&'static str>,
+        fail_on: &'static str,
+    }
+    impl Set<&'static str> for FailingSet {
+        type Error = FailError;
+
+        #[inline]
+        fn get(&self, elem: &&'static str) -> Result<Option<&&'static str>, Self::Error> { Ok(<[&'static str]>::iter(&self.elems).find(|e| *e == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s &'static str>, Self::Error>
+        where
+            &'static str: 's,
+        {
+            Ok(<[&'static str]>::iter(&self.elems))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.elems.len()) }
+    }
+    impl SetSync<&'static str> for FailingSet {
+        #[inline]
+        fn add(&mut self, elem: &'static str) -> Result<bool, Self::Error> {
+            if elem == self.fail_on {
+                return Err(FailError);
+            }
+            let res: Result<bool, Infallible> = SetSync::add(&mut self.elems, elem);
+            Ok(res.unwrap())
+        }
+
+        #[inline]
+        fn clear(&mut self) -> Result<(), Self::Error> {
+            self.elems.clear();
+            Ok(())
+        }
+    }
+
+
+    /// Minimal message that is just an author and a textual payload.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct TestMessage {
+        author_id: String,
+        payload:   String,
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+    impl Message for TestMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+
+    /// Shorthand for creating a [`TestMessage`].
+    fn msg(author_id: &str, payload: &str) -> TestMessage { TestMessage { author_id: author_id.into(), payload: payload.into() } }
+
+    impl ConstructableMessage for TestMessage {
+        #[inline]
+        fn new(author_id: String, payload: String) -> Self { Self { author_id, payload } }
+    }
+
+    /// Minimal action that is just an actor, a basis and extra messages.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestAction {
+        actor_id: String,
+        basis:    TestMessage,
+        extra:    MessageSet<TestMessage>,
+    }
+    impl Actored for TestAction {
+        type ActorId = str;
+
+        #[inline]
+        fn actor_id(&self) -> &Self::ActorId { &self.actor_id }
+    }
+    impl Action for TestAction {
+        type Message = TestMessage;
+
+        #[inline]
+        fn basis(&self) -> &Self::Message { &self.basis }
+
+        #[inline]
+        fn extra(&self) -> &MessageSet<Self::Message> { &self.extra }
+
+        #[inline]
+        fn payload(&self) -> MessageSet<Self::Message> {
+            let mut payload: MessageSet<TestMessage> = self.extra.clone();
+            SetSync::add(&mut payload, self.basis.clone()).unwrap();
+            payload
+        }
+    }
+    impl ConstructableAction for TestAction {
+        #[inline]
+        fn new(actor_id: String, basis: Self::Message, extra: MessageSet<Self::Message>) -> Self { Self { actor_id, basis, extra } }
+    }
+
+    /// An asynchronous set that remembers every element sent through it, and to who.
+    #[derive(Clone, Debug)]
+    struct Outbox<T> {
+        sent: Vec<(Recipient<String>, T)>,
+    }
+    impl<T> Default for Outbox<T> {
+        #[inline]
+        fn default() -> Self { Self { sent: Vec::new() } }
+    }
+    impl<T: PartialEq> Set<T> for Outbox<T> {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &T) -> Result<Option<&T>, Self::Error> { Ok(<[(Recipient<String>, T)]>::iter(&self.sent).map(|(_, e)| e).find(|e| *e == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s T>, Self::Error>
+        where
+            T: 's,
+        {
+            Ok(<[(Recipient<String>, T)]>::iter(&self.sent).map(|(_, e)| e))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.sent.len()) }
+    }
+    impl<T: PartialEq> SetAsync<str, T> for Outbox<T> {
+        #[inline]
+        fn add(&mut self, selector: Recipient<String>, elem: T) -> Result<(), Self::Error> {
+            self.sent.push((selector, elem));
+            Ok(())
+        }
+    }
+
+    /// Agent that does nothing but say goodbye when it is shut down.
+    #[derive(Debug)]
+    struct GoodbyeAgent {
+        id:      String,
+        goodbye: &'static str,
+    }
+    impl Identifiable for GoodbyeAgent {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Agent<str> for GoodbyeAgent {
+        type Error = Infallible;
+
+        #[inline]
+        fn poll<A, S, E, SM, SA>(&mut self, _view: View<Self::Id, A, S, E>, _context: &()) -> Result<Poll<()>, Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            Ok(Poll::Ready(()))
+        }
+
+        #[inline]
+        fn on_shutdown<A, S, E, SM, SA>(&mut self, mut view: View<Self::Id, A, S, E>, _context: &()) -> Result<(), Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            view.state(SM::new(self.id.clone(), self.goodbye.into())).unwrap();
+            Ok(())
+        }
+    }
+
+    /// Error thrown by the [`ScriptedAgent`].
+    #[derive(Debug, PartialEq)]
+    enum ScriptError {
+        /// An error that may go away when trying again. Carries the poll it occurred in.
+        Transient(usize),
+        /// An error that won't. Carries the poll it occurred in.
+        Fatal(usize),
+    }
+    impl Display for ScriptError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+            match self {
+                Self::Transient(poll) => write!(f, "Transient error in poll {poll}"),
+                Self::Fatal(poll) => write!(f, "Fatal error in poll {poll}"),
+            }
+        }
+    }
+    impl error::Error for ScriptError {}
+
+    /// Defines the outcome of a single poll of a [`ScriptedAgent`].
+    #[derive(Clone, Copy, Debug)]
+    enum Step {
+        Pending,
+        Ready,
+        Transient,
+        Fatal,
+    }
+
+    /// Agent that follows a script of poll outcomes, and counts how often it is polled.
+    ///
+    /// Once the script runs out, the agent is [`Poll::Ready`].
+    #[derive(Debug)]
+    struct ScriptedAgent {
+        id:     String,
+        script: Vec<Step>,
+        polls:  usize,
+    }
+    impl ScriptedAgent {
+        /// Constructor for a ScriptedAgent that follows the given script.
+        fn new(script: &[Step]) -> Self { Self { id: "amy".into(), script: script.to_vec(), polls: 0 } }
+    }
+    impl Identifiable for ScriptedAgent {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Agent<str> for ScriptedAgent {
+        type Error = ScriptError;
+
+        #[inline]
+        fn poll<A, S, E, SM, SA>(&mut self, _view: View<Self::Id, A, S, E>, _context: &()) -> Result<Poll<()>, Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            self.polls += 1;
+            match self.script.get(self.polls - 1).copied().unwrap_or(Step::Ready) {
+                Step::Pending => Ok(Poll::Pending),
+                Step::Ready => Ok(Poll::Ready(())),
+                Step::Transient => Err(ScriptError::Transient(self.polls)),
+                Step::Fatal => Err(ScriptError::Fatal(self.polls)),
+            }
+        }
+    }
+
+    /// Agent that states one message, and is then done.
+    #[derive(Debug)]
+    struct StateAgent {
+        id:      String,
+        payload: &'static str,
+    }
+    impl Identifiable for StateAgent {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Agent<str> for StateAgent {
+        type Error = Infallible;
+
+        #[inline]
+        fn poll<A, S, E, SM, SA>(&mut self, mut view: View<Self::Id, A, S, E>, _context: &()) -> Result<Poll<()>, Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            view.state(SM::new(self.id.clone(), self.payload.into())).unwrap();
+            Ok(Poll::Ready(()))
+        }
+    }
+
+    /// Agent that waits until some message is stated, and then enacts it.
+    #[derive(Debug)]
+    struct EnactAgent {
+        id: String,
+    }
+    impl Identifiable for EnactAgent {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Agent<str> for EnactAgent {
+        type Error = Infallible;
+
+        #[inline]
+        fn poll<A, S, E, SM, SA>(&mut self, mut view: View<Self::Id, A, S, E>, _context: &()) -> Result<Poll<()>, Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            let basis: Option<SM> = view.stated.iter().unwrap().next().cloned();
+            match basis {
+                Some(basis) => {
+                    view.enact(SA::new(self.id.clone(), basis, MessageSet::new())).unwrap();
+                    Ok(Poll::Ready(()))
+                },
+                None => Ok(Poll::Pending),
+            }
+        }
+    }
+
+    /// Minimal effect, identified by the fact it effects.
+    #[derive(Debug)]
+    struct TestEffect {
+        affector_id: String,
+        fact:        String,
+    }
+    impl Affectored for TestEffect {
+        type AffectorId = str;
+
+        #[inline]
+        fn affector_id(&self) -> &Self::AffectorId { &self.affector_id }
+    }
+    impl Identifiable for TestEffect {
+        type Id = String;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.fact }
+    }
+    impl Effect for TestEffect {
+        type Fact = String;
+
+        #[inline]
+        fn fact(&self) -> &Self::Fact { &self.fact }
+    }
+
+    /// Minimal denotation that only lists its true facts, and never has any effects.
+    #[derive(Debug)]
+    struct TestDenotation {
+        truths: Vec<String>,
+    }
+    impl Set<String> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &String) -> Result<Option<&String>, Self::Error> { Ok(<[String]>::iter(&self.truths).find(|f| *f == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s String>, Self::Error>
+        where
+            String: 's,
+        {
+            Ok(<[String]>::iter(&self.truths))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.truths.len()) }
+    }
+    impl Map<TestEffect> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, _id: &String) -> Result<Option<&TestEffect>, Self::Error> { Ok(None) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s TestEffect>, Self::Error>
+        where
+            TestEffect: 's,
+        {
+            Ok(std::iter::empty())
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(0) }
+    }
+    impl Denotation for TestDenotation {
+        type Effect = TestEffect;
+        type Fact = String;
+
+        #[inline]
+        fn truth_of(&self, fact: &Self::Fact) -> Option<bool> { Some(<[String]>::contains(&self.truths, fact)) }
+    }
+
+    /// Minimal policy that is a list of facts, which may be negated by prefixing them with `!`.
+    ///
+    /// It is invalid if any fact is both stated and negated.
+    #[derive(Clone, Debug, Default)]
+    struct TestPolicy {
+        facts: Vec<String>,
+    }
+    impl Policy for TestPolicy {
+        type Denotation = TestDenotation;
+
+        #[inline]
+        fn is_valid(&self) -> bool {
+            !<[String]>::iter(&self.facts).any(|f| <[String]>::iter(&self.facts).any(|g| g.strip_prefix('!') == Some(f.as_str())))
+        }
+
+        #[inline]
+        fn truths(&self) -> Self::Denotation { TestDenotation { truths: <[String]>::iter(&self.facts).filter(|f| !f.starts_with('!')).cloned().collect() } }
+
+        #[inline]
+        fn compose(&self, other: Self) -> Self {
+            let mut res: Self = self.clone();
+            res.compose_mut(other);
+            res
+        }
+
+        #[inline]
+        fn compose_mut(&mut self, other: Self) { self.facts.extend(other.facts); }
+    }
+
+    /// Extractor that reads every message payload as a single fact.
+    #[derive(Debug)]
+    struct TestExtractor;
+    impl Extractor<str, str> for TestExtractor {
+        type Policy<'m> = TestPolicy;
+        type Error<'m> = Infallible;
+
+        #[inline]
+        fn extract<'m, M: Message<AuthorId = str, Payload = str>>(&self, msgs: &'m impl Set<M>) -> Result<Self::Policy<'m>, Self::Error<'m>> {
+            Ok(TestPolicy { facts: msgs.iter().unwrap().map(|msg| msg.payload().to_string()).collect() })
+        }
+    }
+
+    /// Agent that audits all enacted actions with the extractor it finds in its context.
+    #[derive(Debug)]
+    struct AuditAgent {
+        id:      String,
+        /// The payload of the basis of every audited action, and whether it was valid.
+        audited: Vec<(String, bool)>,
+    }
+    impl Identifiable for AuditAgent {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Agent<str, ExtractorRegistry<TestExtractor>> for AuditAgent {
+        type Error = Infallible;
+
+        #[inline]
+        fn poll<A, S, E, SM, SA>(&mut self, view: View<Self::Id, A, S, E>, context: &ExtractorRegistry<TestExtractor>) -> Result<Poll<()>, Self::Error>
+        where
+            A: Set<SM>,
+            S: SetAsync<Self::Id, SM>,
+            E: SetAsync<Self::Id, SA>,
+            SM: ConstructableMessage<AuthorId = Self::Id, Payload = str>,
+            SA: ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            let (_, extractor): (&PolicyFormat, &TestExtractor) = context.negotiate(&[PolicyFormat::Slick, PolicyFormat::Datalog]).unwrap();
+            for act in view.enacted.iter().unwrap() {
+                let policy: TestPolicy = extractor.extract(&act.payload()).unwrap();
+                self.audited.push((act.basis().payload().into(), policy.is_valid()));
+            }
+            Ok(Poll::Ready(()))
+        }
+    }
+
+    /// Classifies [`ScriptError`]s as transient or not.
+    fn is_transient(err: &ScriptError) -> bool { matches!(err, ScriptError::Transient(_)) }
+
+    /// Type of the views given to agents in the tests.
+    type TestView<'v> = View<str, &'v Vec<TestMessage>, &'v mut Outbox<TestMessage>, &'v mut Outbox<TestAction>>;
+
+    /// Polls the given agent once with a view on the given sets.
+    fn poll<G: Agent<str, Id = str>>(agent: &mut G, stated: &mut Outbox<TestMessage>, enacted: &mut Outbox<TestAction>) -> Result<Poll<()>, G::Error> {
+        let agreed: Vec<TestMessage> = Vec::new();
+        let view: TestView = View { id: "amy".into(), agreed: &agreed, stated, enacted };
+        agent.poll(view, &())
+    }
+
+    /// Shuts down the given agent with a fresh view, and returns the payloads it stated.
+    fn shutdown<G: Agent<str, Id = str>>(agent: &mut G) -> Vec<String> {
+        let agreed: Vec<TestMessage> = Vec::new();
+        let mut stated: Outbox<TestMessage> = Outbox::default();
+        let mut enacted: Outbox<TestAction> = Outbox::default();
+        let view: TestView = View { id: "amy".into(), agreed: &agreed, stated: &mut stated, enacted: &mut enacted };
+        agent.on_shutdown(view, &()).unwrap();
+
+        // Every message should be stated by the agent itself
+        assert!(<[(Recipient<String>, TestMessage)]>::iter(&stated.sent).all(|(to, msg)| to == &Recipient::One("amy".into()) && msg.author_id == "amy"));
+        stated.sent.into_iter().map(|(_, msg)| msg.payload).collect()
+    }
+
+
+    #[test]
+    fn test_view_stats() {
+        let mut view: View<str, (), Counting<Outbox<TestMessage>, str>, ()> =
+            View { id: "amy".into(), agreed: (), stated: Counting::new(Outbox::default()), enacted: () };
+        assert_eq!(view.stats::<Outbox<TestMessage>>().messages_sent, 0);
+
+        // Script: state two messages, then gossip them around
+        view.state(msg("amy", "foo.")).unwrap();
+        view.state(msg("amy", "bar :- foo.")).unwrap();
+        view.gossip(Recipient::All, msg("amy", "foo.")).unwrap();
+        view.gossip(Recipient::One("bob".into()), msg("amy", "bar :- foo.")).unwrap();
+        view.gossip(Recipient::One("bob".into()), msg("amy", "foo.")).unwrap();
+        view.gossip(Recipient::One("cho".into()), msg("amy", "foo.")).unwrap();
+
+        // Gossiping something unknown is refused, and not counted
+        assert!(matches!(view.gossip(Recipient::All, msg("amy", "baz.")), Err(Error::IllegalGossip { .. })));
+
+        let stats: &GossipStats<String> = view.stats::<Outbox<TestMessage>>();
+        assert_eq!(stats.messages_sent, 6);
+        assert_eq!(stats.bytes_sent, 4 + 11 + 4 + 11 + 4 + 4);
+        assert_eq!(stats.by_recipient.len(), 4);
+        assert_eq!(stats.by_recipient.get(&Recipient::All), Some(&1));
+        assert_eq!(stats.by_recipient.get(&Recipient::One("amy".into())), Some(&2));
+        assert_eq!(stats.by_recipient.get(&Recipient::One("bob".into())), Some(&2));
+        assert_eq!(stats.by_recipient.get(&Recipient::One("cho".into())), Some(&1));
+        assert_eq!(view.stated.inner().sent.len(), 6);
+    }
+
+    #[test]
+    fn test_agent_on_shutdown() {
+        let mut agent: GoodbyeAgent = GoodbyeAgent { id: "amy".into(), goodbye: "goodbye." };
+        assert_eq!(shutdown(&mut agent), vec!["goodbye."]);
+    }
+
+    #[test]
+    fn test_retry_agent_on_shutdown() {
+        let mut agent = RetryAgent::new(GoodbyeAgent { id: "amy".into(), goodbye: "goodbye." }, 3, |_: &Infallible| true);
+        assert_eq!(shutdown(&mut agent), vec!["goodbye."]);
+    }
+
+    #[test]
+    fn test_agent_context() {
+        let mut registry: ExtractorRegistry<TestExtractor> = ExtractorRegistry::new();
+        registry.register(PolicyFormat::Datalog, TestExtractor);
+
+        // One action is justified by a consistent policy, the other is not
+        let agreed: Vec<TestMessage> = Vec::new();
+        let mut stated: Outbox<TestMessage> = Outbox::default();
+        let mut enacted: Outbox<TestAction> = Outbox::default();
+        enacted.sent.push((Recipient::All, TestAction { actor_id: "bob".into(), basis: msg("bob", "a"), extra: [msg("cho", "b")].into() }));
+        enacted.sent.push((Recipient::All, TestAction { actor_id: "cho".into(), basis: msg("cho", "c"), extra: [msg("bob", "!c")].into() }));
+
+        let mut agent: AuditAgent = AuditAgent { id: "amy".into(), audited: Vec::new() };
+        let view: TestView = View { id: "amy".into(), agreed: &agreed, stated: &mut stated, enacted: &mut enacted };
+        assert_eq!(agent.poll(view, &registry).unwrap(), Poll::Ready(()));
+        assert_eq!(agent.audited, vec![("a".into(), true), ("c".into(), false)]);
+    }
+
+    #[test]
+    fn test_retry_agent() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // Fails twice, then succeeds (all in one poll of the wrapper)
+        let mut agent = RetryAgent::new(ScriptedAgent::new(&[Step::Transient, Step::Transient, Step::Ready]), 3, is_transient);
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted), Ok(Poll::Ready(())));
+        assert_eq!(agent.inner().polls, 3);
+    }
+
+    #[test]
+    fn test_retry_agent_fatal() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // Fatal errors are not retried
+        let mut agent = RetryAgent::new(ScriptedAgent::new(&[Step::Fatal, Step::Ready]), 3, is_transient);
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted), Err(ScriptError::Fatal(1)));
+        assert_eq!(agent.inner().polls, 1);
+    }
+
+    #[test]
+    fn test_retry_agent_exhausted() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // Transient errors that outlast the retries give the last error
+        let mut agent = RetryAgent::new(ScriptedAgent::new(&[Step::Transient; 4]), 2, is_transient);
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted), Err(ScriptError::Transient(3)));
+        assert_eq!(agent.inner().polls, 3);
+    }
+
+    #[test]
+    fn test_seq_agent() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // State, then enact what was stated
+        let mut agent: SeqAgent<StateAgent, EnactAgent> = SeqAgent::new(StateAgent { id: "amy".into(), payload: "foo." }, EnactAgent { id: "amy".into() });
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Ready(()));
+        assert_eq!(stated.sent, vec![(Recipient::One("amy".into()), msg("amy", "foo."))]);
+        assert_eq!(enacted.sent.len(), 1);
+        assert_eq!(enacted.sent[0].1, TestAction { actor_id: "amy".into(), basis: msg("amy", "foo."), extra: MessageSet::new() });
+    }
+
+    #[test]
+    fn test_seq_agent_order() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // The second agent is only polled once the first one is ready
+        let mut agent: SeqAgent<ScriptedAgent, ScriptedAgent> =
+            SeqAgent::new(ScriptedAgent::new(&[Step::Pending, Step::Pending, Step::Ready]), ScriptedAgent::new(&[Step::Pending, Step::Ready]));
+        for polls in [(1, 0), (2, 0)] {
+            assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Pending);
+            assert_eq!((agent.first.polls, agent.second.polls), polls);
+        }
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Pending);
+        assert_eq!((agent.first.polls, agent.second.polls), (3, 1));
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Ready(()));
+        assert_eq!((agent.first.polls, agent.second.polls), (3, 2));
+    }
+
+    #[test]
+    fn test_seq_agent_errors() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        let mut agent: SeqAgent<ScriptedAgent, ScriptedAgent> = SeqAgent::new(ScriptedAgent::new(&[Step::Fatal]), ScriptedAgent::new(&[]));
+        assert!(matches!(poll(&mut agent, &mut stated, &mut enacted), Err(ComposedError::First(ScriptError::Fatal(1)))));
+        assert_eq!((agent.first.polls, agent.second.polls), (1, 0));
+
+        let mut agent: SeqAgent<ScriptedAgent, ScriptedAgent> = SeqAgent::new(ScriptedAgent::new(&[]), ScriptedAgent::new(&[Step::Fatal]));
+        assert!(matches!(poll(&mut agent, &mut stated, &mut enacted), Err(ComposedError::Second(ScriptError::Fatal(1)))));
+        assert_eq!((agent.first.polls, agent.second.polls), (1, 1));
+    }
+
+    #[test]
+    fn test_par_agent() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // Enacting waits for the statement made by its sibling, so takes one more round
+        let mut agent: ParAgent<EnactAgent, StateAgent> = ParAgent::new(EnactAgent { id: "amy".into() }, StateAgent { id: "amy".into(), payload: "foo." });
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Pending);
+        assert_eq!(stated.sent, vec![(Recipient::One("amy".into()), msg("amy", "foo."))]);
+        assert!(enacted.sent.is_empty());
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Ready(()));
+        assert_eq!(stated.sent.len(), 1);
+        assert_eq!(enacted.sent.len(), 1);
+        assert_eq!(enacted.sent[0].1, TestAction { actor_id: "amy".into(), basis: msg("amy", "foo."), extra: MessageSet::new() });
+    }
+
+    #[test]
+    fn test_par_agent_order() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // Both are polled every round until they are ready, and the composition is ready when both are
+        let mut agent: ParAgent<ScriptedAgent, ScriptedAgent> =
+            ParAgent::new(ScriptedAgent::new(&[Step::Pending, Step::Ready]), ScriptedAgent::new(&[Step::Pending, Step::Pending, Step::Ready]));
+        for polls in [(1, 1), (2, 2)] {
+            assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Pending);
+            assert_eq!((agent.first.polls, agent.second.polls), polls);
+        }
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Ready(()));
+        assert_eq!((agent.first.polls, agent.second.polls), (2, 3));
+    }
+
+    #[test]
+    fn test_par_agent_errors() {
+        let (mut stated, mut enacted): (Outbox<TestMessage>, Outbox<TestAction>) = (Outbox::default(), Outbox::default());
+
+        // The first error wins, i.e., the second agent is not polled anymore that round
+        let mut agent: ParAgent<ScriptedAgent, ScriptedAgent> =
+            ParAgent::new(ScriptedAgent::new(&[Step::Pending, Step::Fatal]), ScriptedAgent::new(&[Step::Pending, Step::Fatal]));
+        assert_eq!(poll(&mut agent, &mut stated, &mut enacted).unwrap(), Poll::Pending);
+        assert!(matches!(poll(&mut agent, &mut stated, &mut enacted), Err(ComposedError::First(ScriptError::Fatal(2)))));
+        assert_eq!((agent.first.polls, agent.second.polls), (2, 1));
+
+        let mut agent: ParAgent<ScriptedAgent, ScriptedAgent> = ParAgent::new(ScriptedAgent::new(&[Step::Pending]), ScriptedAgent::new(&[Step::Fatal]));
+        assert!(matches!(poll(&mut agent, &mut stated, &mut enacted), Err(ComposedError::Second(ScriptError::Fatal(1)))));
+        assert_eq!((agent.first.polls, agent.second.polls), (1, 1));
+    }
+
+    #[test]
+    fn test_seq_agent_on_shutdown() {
+        let mut agent: SeqAgent<GoodbyeAgent, GoodbyeAgent> =
+            SeqAgent::new(GoodbyeAgent { id: "amy".into(), goodbye: "first." }, GoodbyeAgent { id: "amy".into(), goodbye: "second." });
+        assert_eq!(shutdown(&mut agent), vec!["first.", "second."]);
+    }
+
+    #[test]
+    fn test_par_agent_on_shutdown() {
+        // Nested, to check the reborrowed view is forwarded properly
+        let mut agent: ParAgent<GoodbyeAgent, SeqAgent<GoodbyeAgent, GoodbyeAgent>> = ParAgent::new(
+            GoodbyeAgent { id: "amy".into(), goodbye: "first." },
+            SeqAgent::new(GoodbyeAgent { id: "amy".into(), goodbye: "second." }, GoodbyeAgent { id: "amy".into(), goodbye: "third." }),
+        );
+        assert_eq!(shutdown(&mut agent), vec!["first.", "second.", "third."]);
+    }
+
+    #[test]
+    fn test_view_fork() {
+        let mut view: View<str, Vec<TestMessage>, Outbox<TestMessage>, Outbox<TestAction>> =
+            View { id: "amy".into(), agreed: Vec::new(), stated: Outbox::default(), enacted: Outbox::default() };
+        view.state(msg("amy", "foo.")).unwrap();
+
+        // Speculate in the fork
+        let mut fork: View<str, Vec<TestMessage>, Outbox<TestMessage>, Outbox<TestAction>> = view.fork();
+        fork.state(msg("amy", "bar.")).unwrap();
+        fork.enact(TestAction { actor_id: "amy".into(), basis: msg("amy", "bar."), extra: MessageSet::new() }).unwrap();
+        assert_eq!(fork.stated.sent.len(), 2);
+        assert_eq!(fork.enacted.sent.len(), 1);
+
+        // The original is unaffected
+        assert_eq!(view.stated.sent, vec![(Recipient::One("amy".into()), msg("amy", "foo."))]);
+        assert!(view.enacted.sent.is_empty());
+    }
+
+    #[test]
+    fn test_read_view() {
+        /// Some sub-routine that only needs to query the view.
+        fn count_by(view: ReadView<str, Vec<TestMessage>, Outbox<TestMessage>, Outbox<TestAction>>, author_id: &str) -> usize {
+            view.stated().iter().unwrap().filter(|msg| msg.author_id == author_id).count()
+        }
+
+        let mut view: View<str, Vec<TestMessage>, Outbox<TestMessage>, Outbox<TestAction>> =
+            View { id: "amy".into(), agreed: vec![msg("bob", "agreement.")], stated: Outbox::default(), enacted: Outbox::default() };
+        view.state(msg("amy", "foo.")).unwrap();
+        view.state(msg("amy", "bar.")).unwrap();
+        view.stated.sent.push((Recipient::All, msg("bob", "baz.")));
+
+        let read: ReadView<str, Vec<TestMessage>, Outbox<TestMessage>, Outbox<TestAction>> = view.as_read();
+        assert_eq!(read.id(), "amy");
+        assert_eq!(read.agreed(), &vec![msg("bob", "agreement.")]);
+        assert_eq!(count_by(read, "amy"), 2);
+        assert_eq!(count_by(read, "bob"), 1);
+        assert_eq!(read.enacted().len().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_view_active_agents() {
+        let mut view: View<str, (), Outbox<TestMessage>, ()> = View { id: "amy".into(), agreed: (), stated: Outbox::default(), enacted: () };
+        assert_eq!(view.active_agents::<TestMessage>().unwrap().count(), 0);
+
+        for (author_id, payload) in [("bob", "a."), ("amy", "b."), ("bob", "c."), ("bob", "d."), ("amy", "e."), ("bob", "f.")] {
+            view.stated.sent.push((Recipient::All, msg(author_id, payload)));
+        }
+        assert_eq!(view.active_agents::<TestMessage>().unwrap().collect::<Vec<&str>>(), vec!["bob", "amy"]);
+    }
+
+    #[test]
+    fn test_view_agree() {
+        let mut view: View<str, FailingSet, (), ()> =
+            View { id: "amy".into(), agreed: FailingSet { elems: vec!["old1", "old2"], fail_on: "fail" }, stated: (), enacted: () };
+        view.agree(["new1", "new2"]).unwrap();
+        assert_eq!(view.agreed.elems, vec!["new1", "new2"]);
+    }
+
+    #[test]
+    fn test_view_agree_rollback() {
+        let mut view: View<str, FailingSet, (), ()> =
+            View { id: "amy".into(), agreed: FailingSet { elems: vec!["old1", "old2"], fail_on: "fail" }, stated: (), enacted: () };
+        assert!(matches!(view.agree(["new1", "new2", "fail", "new4"]), Err(Error::Set(FailError))));
+        assert_eq!(view.agreed.elems, vec!["old1", "old2"]);
+    }
+}
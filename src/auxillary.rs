@@ -4,7 +4,7 @@
 //  Created:
 //    10 Dec 2024, 10:54:37
 //  Last edited:
-//    13 Jan 2025, 14:25:33
+//    15 Oct 2026, 16:13:16
 //  Auto updated?
 //    Yes
 //
@@ -13,9 +13,15 @@
 //!   ontology.
 //
 
+use std::borrow::Borrow;
+use std::fmt::{Display, Formatter, Result as FResult};
 use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::Arc;
 
 use auto_traits::pointer_impls;
+#[cfg(feature = "derive")]
+pub use justact_derive::{Actored, Affectored, Authored, Identifiable};
 
 
 /***** LIBRARY *****/
@@ -59,6 +65,55 @@ pub trait Affectored {
     fn affector_id(&self) -> &Self::AffectorId;
 }
 
+/// Marks the author role for [`Related`] (see [`Authored`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Author;
+
+/// Marks the actor role for [`Related`] (see [`Actored`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Actor;
+
+/// Marks the affector role for [`Related`] (see [`Affectored`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Affector;
+
+/// Abstractly defines an object which is related to some agent in a particular role.
+///
+/// This unifies [`Authored`], [`Actored`] and [`Affectored`], such that generic code can abstract
+/// over "the X-id of this object". It is implemented automatically for anything implementing one
+/// of those traits, using [`Author`], [`Actor`] or [`Affector`] as the role, respectively.
+///
+/// # Generics
+/// - `R`: The role in which the agent is related to this object.
+pub trait Related<R> {
+    /// Some identifier for the related agent.
+    type Id: ?Sized + Eq + Hash;
+
+    /// Returns the ID of the agent related to this object in role `R`.
+    ///
+    /// # Returns
+    /// A reference to a [`Related::Id`] that describes the unique ID of the related agent.
+    fn related_id(&self) -> &Self::Id;
+}
+impl<T: ?Sized + Authored> Related<Author> for T {
+    type Id = T::AuthorId;
+
+    #[inline]
+    fn related_id(&self) -> &Self::Id { self.author_id() }
+}
+impl<T: ?Sized + Actored> Related<Actor> for T {
+    type Id = T::ActorId;
+
+    #[inline]
+    fn related_id(&self) -> &Self::Id { self.actor_id() }
+}
+impl<T: ?Sized + Affectored> Related<Affector> for T {
+    type Id = T::AffectorId;
+
+    #[inline]
+    fn related_id(&self) -> &Self::Id { self.affector_id() }
+}
+
 
 
 /// Abstractly defines an object which is uniquely identifiable by something.
@@ -73,3 +128,187 @@ pub trait Identifiable {
     /// A reference to an [`Identifiable::Id`] that describes the unique ID of this object.
     fn id(&self) -> &Self::Id;
 }
+
+/// Abstractly defines an object of which the identifier can be (re)assigned after construction.
+///
+/// This is mostly useful for objects that are [`Default`]-constructed first, and then stamped with
+/// an identifier (see, e.g., [`MapSync::get_or_default()`](crate::collections::map::MapSync::get_or_default())).
+pub trait IdentifiableMut: Identifiable {
+    /// Changes the ID of this object.
+    ///
+    /// After calling this function, [`Identifiable::id()`] must return something equal to `id`.
+    ///
+    /// # Arguments
+    /// - `id`: The new identifier of this object.
+    fn set_id(&mut self, id: &Self::Id);
+}
+
+
+
+/// Defines a cheaply clonable identifier that shares its contents.
+///
+/// Identifiers are (implicitly) cloned whenever they're used as keys in a
+/// [`Map`](crate::collections::map::Map) (e.g., the [`HashMap`](std::collections::HashMap)
+/// implementation stores [owned](ToOwned) IDs). For messages that are stored in many maps, this
+/// duplicates the identifier's bytes every time. By using an `ArcId` as [`Identifiable::Id`]
+/// instead, every copy shares the same allocation.
+///
+/// Comparison and hashing are done by **content**, not by pointer. Hence, looking up an `ArcId`
+/// by its borrowed contents (e.g., a `&str` for an `ArcId<str>`) works as expected.
+///
+/// # Generics
+/// - `T`: The type of the shared identifier, e.g., [`str`] or `[u8]`.
+#[derive(Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ArcId<T: ?Sized>(pub Arc<T>);
+
+// Constructors
+impl<T: ?Sized> ArcId<T> {
+    /// Constructor for the ArcId.
+    ///
+    /// # Arguments
+    /// - `id`: Some [`Arc`] with the identifier to share.
+    ///
+    /// # Returns
+    /// A new ArcId wrapping `id`.
+    #[inline]
+    pub const fn new(id: Arc<T>) -> Self { Self(id) }
+}
+impl<T: ?Sized> Clone for ArcId<T> {
+    #[inline]
+    fn clone(&self) -> Self { Self(self.0.clone()) }
+}
+impl<T: ?Sized> From<Arc<T>> for ArcId<T> {
+    #[inline]
+    fn from(value: Arc<T>) -> Self { Self(value) }
+}
+impl<'a, T: ?Sized> From<&'a T> for ArcId<T>
+where
+    Arc<T>: From<&'a T>,
+{
+    #[inline]
+    fn from(value: &'a T) -> Self { Self(Arc::from(value)) }
+}
+
+// ArcId
+impl<T: ?Sized> ArcId<T> {
+    /// Checks whether two `ArcId`s share the same allocation.
+    ///
+    /// Note that this is stricter than [`PartialEq`], which compares the identifiers by content.
+    ///
+    /// # Arguments
+    /// - `other`: Some other ArcId to compare with.
+    ///
+    /// # Returns
+    /// True if both point to the same allocation, false otherwise.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+
+    /// Returns the wrapped [`Arc`].
+    ///
+    /// # Returns
+    /// The internal `Arc<T>`.
+    #[inline]
+    pub fn into_inner(self) -> Arc<T> { self.0 }
+}
+
+// Ops
+impl<T: ?Sized + Display> Display for ArcId<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { self.0.fmt(f) }
+}
+impl<T: ?Sized> AsRef<T> for ArcId<T> {
+    #[inline]
+    fn as_ref(&self) -> &T { &self.0 }
+}
+impl<T: ?Sized> Borrow<T> for ArcId<T> {
+    #[inline]
+    fn borrow(&self) -> &T { &self.0 }
+}
+impl<T: ?Sized> Deref for ArcId<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::collections::map::{Map, MapSync};
+
+
+    /// Minimal message with a shared identifier.
+    #[derive(Clone, Debug)]
+    struct TestMessage {
+        id:        ArcId<str>,
+        author_id: String,
+    }
+    impl Identifiable for TestMessage {
+        type Id = ArcId<str>;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+
+
+    /// Minimal action with an actor.
+    struct TestAction {
+        actor_id: u32,
+    }
+    impl Actored for TestAction {
+        type ActorId = u32;
+
+        #[inline]
+        fn actor_id(&self) -> &Self::ActorId { &self.actor_id }
+    }
+
+    /// Generic code over any role.
+    fn related<R, T: Related<R>>(obj: &T) -> &T::Id { obj.related_id() }
+
+
+    #[test]
+    fn test_related() {
+        let msg = TestMessage { id: ArcId::from("amy:0"), author_id: "amy".into() };
+        let act = TestAction { actor_id: 42 };
+        assert_eq!(related::<Author, _>(&msg), "amy");
+        assert_eq!(related::<Actor, _>(&act), &42);
+
+        // Also through pointers
+        assert_eq!(related::<Author, _>(&&msg), "amy");
+    }
+
+    #[test]
+    fn test_arc_id_shared() {
+        let msg = TestMessage { id: ArcId::from("amy:0"), author_id: "amy".into() };
+        let mut by_id: HashMap<ArcId<str>, TestMessage> = HashMap::new();
+        let mut by_author: HashMap<ArcId<str>, TestMessage> = HashMap::new();
+        assert!(by_id.add(msg.clone()).unwrap().is_none());
+        assert!(by_author.add(msg.clone()).unwrap().is_none());
+
+        // Both maps store the same allocation as key
+        let key1: &ArcId<str> = by_id.keys().next().unwrap();
+        let key2: &ArcId<str> = by_author.keys().next().unwrap();
+        assert!(key1.ptr_eq(key2));
+        assert!(key1.ptr_eq(&msg.id));
+        assert!(!key1.ptr_eq(&ArcId::from("amy:0")));
+        assert_eq!(key1, &ArcId::from("amy:0"));
+
+        // Lookups work by content, both by `ArcId` and by `&str`
+        assert_eq!(Map::get(&by_id, &ArcId::from("amy:0")).unwrap().map(|m| m.author_id()), Some("amy"));
+        assert_eq!(HashMap::get(&by_author, "amy:0").map(|m| m.author_id()), Some("amy"));
+        assert!(HashMap::get(&by_author, "amy:1").is_none());
+    }
+}
@@ -0,0 +1,179 @@
+//  ADAPTORS.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:10:14
+//  Last edited:
+//    15 Oct 2026, 16:14:08
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines adaptors that view [`Set`]s as [`Map`]s and vice versa.
+//
+
+use super::map::Map;
+use super::set::Set;
+use crate::auxillary::Identifiable;
+
+
+/***** LIBRARY *****/
+/// Views an (element-based) [`Set`] of [`Identifiable`] elements as an (ID-based) [`Map`].
+///
+/// Lookups by ID are done by iterating over the set and comparing every element's
+/// [`Identifiable::id()`]. As such, these are linear in the size of the set.
+///
+/// Note that a set may contain multiple elements with the same ID (if they differ otherwise). In
+/// that case, lookups return the first one found.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SetAsMap<S>(pub S);
+
+// Collections
+impl<E, S> Map<E> for SetAsMap<S>
+where
+    S: Set<E>,
+{
+    type Error = S::Error;
+
+    #[inline]
+    fn get(&self, id: &<E as Identifiable>::Id) -> Result<Option<&E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        Ok(self.0.iter()?.find(|elem| elem.id() == id))
+    }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's + Identifiable,
+    {
+        self.0.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.0.len() }
+}
+
+
+
+/// Views an (ID-based) [`Map`] as an (element-based) [`Set`].
+///
+/// Lookups by element are done by first looking up the element's [`Identifiable::id()`] in the
+/// map, and then comparing the found element in full. As such, an element is only in this set if
+/// the map contains an element with the same ID **and** the same contents.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MapAsSet<M>(pub M);
+
+// Collections
+impl<E, M> Set<E> for MapAsSet<M>
+where
+    E: Identifiable + PartialEq,
+    M: Map<E>,
+{
+    type Error = M::Error;
+
+    #[inline]
+    fn get(&self, elem: &E) -> Result<Option<&E>, Self::Error> { Ok(self.0.get(elem.id())?.filter(|other| *other == elem)) }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's,
+    {
+        self.0.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.0.len() }
+}
+
+
+
+/// Allows any (element-based) [`Set`] to be cheaply viewed as an (ID-based) [`Map`].
+pub trait AsIdSet<E>: Set<E> {
+    /// Views this set as a [`Map`].
+    ///
+    /// See [`SetAsMap`] for more information.
+    ///
+    /// # Returns
+    /// A [`SetAsMap`] that borrows this set.
+    fn as_id_set(&self) -> SetAsMap<&Self>;
+}
+impl<E, T: Set<E>> AsIdSet<E> for T {
+    #[inline]
+    fn as_id_set(&self) -> SetAsMap<&Self> { SetAsMap(self) }
+}
+
+/// Allows any (ID-based) [`Map`] to be cheaply viewed as an (element-based) [`Set`].
+pub trait AsElemSet<E>: Map<E> {
+    /// Views this map as a [`Set`].
+    ///
+    /// See [`MapAsSet`] for more information.
+    ///
+    /// # Returns
+    /// A [`MapAsSet`] that borrows this map.
+    fn as_elem_set(&self) -> MapAsSet<&Self>;
+}
+impl<E, T: Map<E>> AsElemSet<E> for T {
+    #[inline]
+    fn as_elem_set(&self) -> MapAsSet<&Self> { MapAsSet(self) }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Minimal message with an identifier.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct TestMessage {
+        id:      &'static str,
+        payload: &'static str,
+    }
+    impl Identifiable for TestMessage {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { self.id }
+    }
+
+    /// Shorthand for creating a [`TestMessage`].
+    fn msg(id: &'static str, payload: &'static str) -> TestMessage { TestMessage { id, payload } }
+
+
+    #[test]
+    fn test_set_as_map() {
+        let msgs: Vec<TestMessage> = vec![msg("a", "foo."), msg("b", "bar."), msg("c", "baz.")];
+        let map = AsIdSet::<TestMessage>::as_id_set(&msgs);
+        assert_eq!(Map::get(&map, "b").unwrap(), Some(&msg("b", "bar.")));
+        assert_eq!(Map::get(&map, "d").unwrap(), None);
+        assert_eq!(Map::len(&map).unwrap(), 3);
+
+        // Back to a set again
+        let set = map.as_elem_set();
+        assert_eq!(Set::get(&set, &msg("a", "foo.")).unwrap(), Some(&msg("a", "foo.")));
+        assert_eq!(Set::get(&set, &msg("a", "baz.")).unwrap(), None);
+        assert_eq!(Set::iter(&set).unwrap().cloned().collect::<Vec<TestMessage>>(), msgs);
+    }
+
+    #[test]
+    fn test_map_as_set() {
+        let msgs: Vec<TestMessage> = vec![msg("a", "foo."), msg("b", "bar."), msg("c", "baz.")];
+        let set = AsElemSet::<TestMessage>::as_elem_set(&msgs);
+        assert_eq!(Set::get(&set, &msg("c", "baz.")).unwrap(), Some(&msg("c", "baz.")));
+        assert_eq!(Set::get(&set, &msg("c", "quux.")).unwrap(), None);
+        assert_eq!(Set::len(&set).unwrap(), 3);
+
+        // Back to a map again
+        let map = set.as_id_set();
+        assert_eq!(Map::get(&map, "c").unwrap(), Some(&msg("c", "baz.")));
+        assert_eq!(Map::get(&map, "d").unwrap(), None);
+        assert_eq!(Map::iter(&map).unwrap().cloned().collect::<Vec<TestMessage>>(), msgs);
+    }
+}
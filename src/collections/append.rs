@@ -0,0 +1,306 @@
+//  APPEND.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:45:16
+//  Last edited:
+//    15 Oct 2026, 14:51:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a thread-safe, append-only log that can be read while it is
+//!   being appended to.
+//
+
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::set::{Set, SetSync};
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`AppendLog`].
+#[derive(Debug)]
+pub enum AppendLogError {
+    /// Attempted to remove elements from the log.
+    Unsupported,
+}
+impl Display for AppendLogError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::Unsupported => write!(f, "Cannot remove elements from an append-only log"),
+        }
+    }
+}
+impl Error for AppendLogError {}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The (log2 of the) number of elements in the first chunk of an [`AppendLog`].
+const FIRST_CHUNK_BITS: u32 = 5;
+
+/// The maximum number of chunks an [`AppendLog`] can have.
+///
+/// Every chunk is twice as large as the previous one, so this is the most chunks for which the
+/// index of every slot (shifted by the size of the first chunk) still fits in a [`usize`].
+const MAX_CHUNKS: usize = (usize::BITS - FIRST_CHUNK_BITS) as usize;
+
+/// The maximum number of elements an [`AppendLog`] can hold.
+///
+/// This is the total number of slots in all [`MAX_CHUNKS`] chunks, i.e., `usize::MAX - 31` on
+/// 64-bit platforms.
+const CAPACITY: usize = (1 << FIRST_CHUNK_BITS) * ((1 << MAX_CHUNKS) - 1);
+
+/// A single chunk of slots in an [`AppendLog`].
+type Chunk<E> = Box<[OnceLock<E>]>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// A thread-safe [`Set`] that can only be appended to.
+///
+/// This is intended for runtimes that never delete stated messages. Elements are stored in chunks
+/// that, once allocated, never move. Hence, references to elements remain valid for as long as the
+/// log is borrowed, even while other threads are appending to it.
+///
+/// # Concurrency model
+/// Elements can be appended through a shared reference using [`AppendLog::push()`]. Every push
+/// first reserves a unique slot by atomically incrementing a counter, then allocates the slot's
+/// chunk if nobody did so yet, and finally writes the element into its slot. No global write lock
+/// is taken; threads only ever wait for each other when they race to allocate the same chunk.
+///
+/// Readers see an element as soon as its slot is written. Because pushes may complete out of
+/// order, readers may temporarily see a later element before an earlier one. Once all pushes have
+/// completed (e.g., after joining the pushing threads), the log contains every element in order of
+/// reservation.
+///
+/// Note that [`AppendLog::push()`] does **not** check for duplicates, as that cannot be done
+/// atomically with the append. [`SetSync::add()`], which requires exclusive access, does.
+///
+/// Elements can never be removed; [`SetSync::clear()`] always fails with
+/// [`AppendLogError::Unsupported`].
+///
+/// # Generics
+/// - `E`: The type of elements stored in the log.
+pub struct AppendLog<E> {
+    /// The chunks of slots. Chunk `i` has `2^(FIRST_CHUNK_BITS + i)` slots.
+    chunks:   [OnceLock<Chunk<E>>; MAX_CHUNKS],
+    /// The number of slots reserved so far.
+    reserved: AtomicUsize,
+    /// The number of slots written so far.
+    len:      AtomicUsize,
+}
+
+// Constructors
+impl<E> Default for AppendLog<E> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<E> AppendLog<E> {
+    /// Constructor for the AppendLog that initializes it as empty.
+    ///
+    /// # Returns
+    /// A new AppendLog without any elements in it.
+    #[inline]
+    pub fn new() -> Self { Self { chunks: std::array::from_fn(|_| OnceLock::new()), reserved: AtomicUsize::new(0), len: AtomicUsize::new(0) } }
+}
+impl<E: Debug> Debug for AppendLog<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { f.debug_list().entries(self.elems()).finish() }
+}
+impl<E> FromIterator<E> for AppendLog<E> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        let log: Self = Self::new();
+        for elem in iter {
+            log.push(elem);
+        }
+        log
+    }
+}
+
+// Ops
+impl<E> AppendLog<E> {
+    /// Finds the chunk and the offset within that chunk of a given slot.
+    ///
+    /// # Arguments
+    /// - `index`: The index of the slot to find. Must be smaller than [`CAPACITY`].
+    ///
+    /// # Returns
+    /// A tuple with the index of the chunk and the offset within that chunk.
+    #[inline]
+    fn locate(index: usize) -> (usize, usize) {
+        // Shift the index such that chunk boundaries fall on powers of two
+        let shifted: usize = index + (1 << FIRST_CHUNK_BITS);
+        let bits: u32 = usize::BITS - 1 - shifted.leading_zeros();
+        ((bits - FIRST_CHUNK_BITS) as usize, shifted - (1 << bits))
+    }
+
+    /// Returns an iterator over all written elements, in order of reservation.
+    #[inline]
+    fn elems(&self) -> impl '_ + Iterator<Item = &E> {
+        let reserved: usize = self.reserved.load(Ordering::Acquire);
+        let last: usize = if reserved == 0 { 0 } else { Self::locate(reserved - 1).0 + 1 };
+        self.chunks[..last].iter().filter_map(OnceLock::get).flat_map(|chunk| chunk.iter().filter_map(OnceLock::get))
+    }
+}
+
+// AppendLog
+impl<E> AppendLog<E> {
+    /// Appends an element to the log.
+    ///
+    /// This can be called concurrently from multiple threads. Note that it does **not** check
+    /// whether the element is already in the log.
+    ///
+    /// # Arguments
+    /// - `elem`: The element to append.
+    ///
+    /// # Panics
+    /// This function panics if the log is full, i.e., if it already has `usize::MAX - 31`
+    /// elements (on 64-bit platforms).
+    #[inline]
+    pub fn push(&self, elem: E) {
+        // NOTE: Only reserve if there is space left, so that a full log doesn't wrap around
+        let index: usize = match self.reserved.fetch_update(Ordering::AcqRel, Ordering::Acquire, |r| (r < CAPACITY).then_some(r + 1)) {
+            Ok(index) => index,
+            Err(_) => panic!("Cannot push to an AppendLog that is full"),
+        };
+        let (chunk, offset): (usize, usize) = Self::locate(index);
+        let chunk: &Chunk<E> = self.chunks[chunk].get_or_init(|| (0..1usize << (FIRST_CHUNK_BITS as usize + chunk)).map(|_| OnceLock::new()).collect());
+        // NOTE: Every index is reserved only once, so the slot is guaranteed to be empty
+        let _ = chunk[offset].set(elem);
+        self.len.fetch_add(1, Ordering::Release);
+    }
+
+    /// Returns the number of elements written to the log.
+    ///
+    /// Note that, while other threads are pushing, this may immediately be outdated.
+    ///
+    /// # Returns
+    /// The number of elements in the log.
+    #[inline]
+    pub fn len(&self) -> usize { self.len.load(Ordering::Acquire) }
+
+    /// Returns whether any elements were written to the log.
+    ///
+    /// # Returns
+    /// True if there are **no** elements in the log, false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+// Collections
+impl<E> Set<E> for AppendLog<E>
+where
+    E: PartialEq,
+{
+    type Error = AppendLogError;
+
+    #[inline]
+    fn get(&self, elem: &E) -> Result<Option<&E>, Self::Error> { Ok(self.elems().find(|e| *e == elem)) }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's,
+    {
+        Ok(self.elems())
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { Ok(<Self>::len(self)) }
+}
+impl<E> SetSync<E> for AppendLog<E>
+where
+    E: PartialEq,
+{
+    #[inline]
+    fn add(&mut self, elem: E) -> Result<bool, Self::Error> {
+        if self.elems().any(|e| e == &elem) {
+            return Ok(true);
+        }
+        self.push(elem);
+        Ok(false)
+    }
+
+    /// Always fails, as elements cannot be removed from an append-only log.
+    ///
+    /// # Errors
+    /// This function always errors with [`AppendLogError::Unsupported`].
+    #[inline]
+    fn clear(&mut self) -> Result<(), Self::Error> { Err(AppendLogError::Unsupported) }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_append_log_locate() {
+        assert_eq!(AppendLog::<()>::locate(0), (0, 0));
+        assert_eq!(AppendLog::<()>::locate(31), (0, 31));
+        assert_eq!(AppendLog::<()>::locate(32), (1, 0));
+        assert_eq!(AppendLog::<()>::locate(95), (1, 63));
+        assert_eq!(AppendLog::<()>::locate(96), (2, 0));
+        assert_eq!(AppendLog::<()>::locate(CAPACITY - 1), (MAX_CHUNKS - 1, (1 << (FIRST_CHUNK_BITS as usize + MAX_CHUNKS - 1)) - 1));
+    }
+
+    #[test]
+    fn test_append_log_push_concurrent() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 1000;
+
+        let log: AppendLog<usize> = AppendLog::new();
+        std::thread::scope(|s| {
+            for t in 0..THREADS {
+                let log: &AppendLog<usize> = &log;
+                s.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        log.push(t * PER_THREAD + i);
+                        // Read while others are still appending
+                        assert!(Set::iter(log).unwrap().count() <= THREADS * PER_THREAD);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(log.len(), THREADS * PER_THREAD);
+        assert_eq!(Set::len(&log).unwrap(), THREADS * PER_THREAD);
+        let mut elems: Vec<usize> = Set::iter(&log).unwrap().copied().collect();
+        elems.sort();
+        assert_eq!(elems, (0..THREADS * PER_THREAD).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_append_log_add_dedups() {
+        let mut log: AppendLog<&str> = AppendLog::new();
+        assert!(!SetSync::add(&mut log, "a").unwrap());
+        assert!(!SetSync::add(&mut log, "b").unwrap());
+        assert!(SetSync::add(&mut log, "a").unwrap());
+        assert_eq!(log.len(), 2);
+        assert_eq!(Set::get(&log, &"a").unwrap(), Some(&"a"));
+        assert_eq!(Set::get(&log, &"c").unwrap(), None);
+    }
+
+    #[test]
+    fn test_append_log_clear_unsupported() {
+        let mut log: AppendLog<&str> = ["a", "b"].into_iter().collect();
+        assert!(matches!(SetSync::clear(&mut log), Err(AppendLogError::Unsupported)));
+        assert_eq!(log.len(), 2);
+    }
+}
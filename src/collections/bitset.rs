@@ -0,0 +1,313 @@
+//  BITSET.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:27:41
+//  Last edited:
+//    15 Oct 2026, 14:53:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a compact set of integer identifiers backed by a bit vector.
+//
+
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+
+/***** AUXILLARY *****/
+/// The number of bits stored per word in a [`BitsetSet`].
+const WORD_BITS: u64 = u64::BITS as u64;
+
+
+
+
+
+/***** LIBRARY *****/
+/// A set of integer identifiers that is stored as a bit vector.
+///
+/// Membership checks, insertions and removals are all O(1), and every identifier only takes a
+/// single bit. However, the bit vector always spans from `0` up to the largest identifier ever
+/// inserted. As such, this set assumes that the identifiers are **dense**, i.e., that most of the
+/// range `0..=max` is actually in the set. For sparse identifiers (e.g., random hashes), a
+/// [`HashSet`](std::collections::HashSet) is much more compact.
+///
+/// As a rule of thumb, a `HashSet<u64>` spends at least 8 bytes (and typically around 16 after
+/// accounting for its load factor and control bytes) per identifier, whereas a `BitsetSet` spends
+/// `(max + 1) / 8` bytes in total. Hence the bitset wins as soon as more than roughly one in 64
+/// identifiers in the range is present. Use [`BitsetSet::heap_size()`] to compare in practice.
+///
+/// Note that this set does not implement [`Set`](super::set::Set) or
+/// [`SetSync`](super::set::SetSync), as those hand out references to stored elements; a bitset
+/// does not store the identifiers themselves. Instead, it yields them by value.
+#[derive(Clone, Default)]
+pub struct BitsetSet {
+    /// The bits, where bit `i % 64` of word `i / 64` encodes whether `i` is in the set.
+    words: Vec<u64>,
+    /// The number of bits set, cached.
+    len:   usize,
+}
+
+// Constructors
+impl BitsetSet {
+    /// Constructor for the BitsetSet that initializes it as empty.
+    ///
+    /// # Returns
+    /// A new BitsetSet without any identifiers in it.
+    #[inline]
+    pub const fn new() -> Self { Self { words: Vec::new(), len: 0 } }
+
+    /// Constructor for the BitsetSet that initializes it as empty, but with space for identifiers
+    /// up to (and including) the given maximum.
+    ///
+    /// # Arguments
+    /// - `max`: The largest identifier to pre-allocate space for.
+    ///
+    /// # Returns
+    /// A new BitsetSet without any identifiers in it.
+    #[inline]
+    pub fn with_max(max: u64) -> Self { Self { words: vec![0; Self::word_of(max) + 1], len: 0 } }
+}
+impl Debug for BitsetSet {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { f.debug_set().entries(self.iter()).finish() }
+}
+impl Eq for BitsetSet {}
+impl PartialEq for BitsetSet {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // Compare the words, treating missing words as zero
+        let (short, long): (&[u64], &[u64]) =
+            if self.words.len() <= other.words.len() { (&self.words, &other.words) } else { (&other.words, &self.words) };
+        short == &long[..short.len()] && long[short.len()..].iter().all(|word| *word == 0)
+    }
+}
+impl FromIterator<u64> for BitsetSet {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut set: Self = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+impl Extend<u64> for BitsetSet {
+    #[inline]
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+// Ops
+impl BitsetSet {
+    /// Returns the index of the word containing the given identifier.
+    #[inline]
+    fn word_of(id: u64) -> usize { usize::try_from(id / WORD_BITS).unwrap_or_else(|_| panic!("Identifier {id} is too large for a BitsetSet on this platform")) }
+
+    /// Returns the mask selecting the given identifier in its word.
+    #[inline]
+    const fn mask_of(id: u64) -> u64 { 1 << (id % WORD_BITS) }
+}
+
+// BitsetSet
+impl BitsetSet {
+    /// Checks whether an identifier is in the set.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier to check for.
+    ///
+    /// # Returns
+    /// True if it is in the set, false otherwise.
+    #[inline]
+    pub fn contains(&self, id: u64) -> bool {
+        match usize::try_from(id / WORD_BITS).ok().and_then(|word| self.words.get(word)) {
+            Some(word) => word & Self::mask_of(id) != 0,
+            None => false,
+        }
+    }
+
+    /// Adds an identifier to the set.
+    ///
+    /// Note that this grows the set to span up to `id` if it doesn't already.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier to add.
+    ///
+    /// # Returns
+    /// True if the identifier was new, false if it was already in the set.
+    ///
+    /// # Panics
+    /// This function panics if `id / 64` does not fit in a [`usize`].
+    #[inline]
+    pub fn insert(&mut self, id: u64) -> bool {
+        let word: usize = Self::word_of(id);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let mask: u64 = Self::mask_of(id);
+        let new: bool = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        if new {
+            self.len += 1;
+        }
+        new
+    }
+
+    /// Removes an identifier from the set.
+    ///
+    /// Note that this never shrinks the set. Use [`BitsetSet::shrink_to_fit()`] for that.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier to remove.
+    ///
+    /// # Returns
+    /// True if the identifier was in the set, false otherwise.
+    #[inline]
+    pub fn remove(&mut self, id: u64) -> bool {
+        let mask: u64 = Self::mask_of(id);
+        match usize::try_from(id / WORD_BITS).ok().and_then(|word| self.words.get_mut(word)) {
+            Some(word) if *word & mask != 0 => {
+                *word &= !mask;
+                self.len -= 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Removes all identifiers from the set.
+    ///
+    /// Note that this keeps the allocated memory around.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+        self.len = 0;
+    }
+
+    /// Releases the memory used for identifiers beyond the largest one in the set.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+        self.words.shrink_to_fit();
+    }
+
+    /// Returns an iterator over the identifiers in the set, in ascending order.
+    ///
+    /// # Returns
+    /// An [`Iterator`] yielding the identifiers by value.
+    #[inline]
+    pub fn iter(&self) -> impl '_ + Iterator<Item = u64> {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let word: u64 = *word;
+            (0..WORD_BITS).filter(move |bit| word & (1 << bit) != 0).map(move |bit| i as u64 * WORD_BITS + bit)
+        })
+    }
+
+    /// Returns the number of identifiers in the set.
+    ///
+    /// # Returns
+    /// The number of identifiers in the set.
+    #[inline]
+    pub const fn len(&self) -> usize { self.len }
+
+    /// Returns whether there are any identifiers in the set.
+    ///
+    /// # Returns
+    /// True if there are **no** identifiers in the set, false otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns the number of bytes this set has allocated on the heap.
+    ///
+    /// This can be used to compare its footprint against other sets for the identifiers at hand.
+    ///
+    /// # Returns
+    /// The number of bytes allocated to store the bits.
+    #[inline]
+    pub fn heap_size(&self) -> usize { self.words.capacity() * std::mem::size_of::<u64>() }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+
+    #[test]
+    fn test_bitset_set_dense() {
+        let mut set: BitsetSet = BitsetSet::new();
+        for id in 0..1000 {
+            assert!(set.insert(id));
+        }
+        assert!(!set.insert(500));
+        assert_eq!(set.len(), 1000);
+        assert!((0..1000).all(|id| set.contains(id)));
+        assert!(!set.contains(1000));
+
+        // Remove every odd identifier
+        for id in (1..1000).step_by(2) {
+            assert!(set.remove(id));
+        }
+        assert!(!set.remove(1));
+        assert!(!set.remove(100_000));
+        assert_eq!(set.len(), 500);
+        assert_eq!(set.iter().collect::<Vec<u64>>(), (0..1000).step_by(2).collect::<Vec<u64>>());
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.iter().next(), None);
+    }
+
+    #[test]
+    fn test_bitset_set_word_boundary() {
+        let mut set: BitsetSet = BitsetSet::new();
+        for id in [0, 62, 63, 64, 127, 128] {
+            assert!(set.insert(id));
+        }
+        for id in [0, 62, 63, 64, 127, 128] {
+            assert!(set.contains(id), "Expected {id} to be in the set");
+        }
+        for id in [1, 61, 65, 126, 129] {
+            assert!(!set.contains(id), "Expected {id} not to be in the set");
+        }
+        assert_eq!(set.iter().collect::<Vec<u64>>(), vec![0, 62, 63, 64, 127, 128]);
+
+        // Removing the top bit of a word shouldn't affect its neighbours
+        assert!(set.remove(63));
+        assert!(set.contains(62));
+        assert!(set.contains(64));
+        assert_eq!(set.len(), 5);
+
+        // Trailing empty words do not matter for equality
+        assert!(set.remove(128));
+        assert_eq!(set, BitsetSet::from_iter([0, 62, 64, 127]));
+        set.shrink_to_fit();
+        assert_eq!(set.heap_size(), 2 * std::mem::size_of::<u64>());
+        assert_eq!(set, BitsetSet::from_iter([0, 62, 64, 127]));
+    }
+
+    #[test]
+    fn test_bitset_set_memory() {
+        const IDS: u64 = 1_000_000;
+
+        let mut bitset: BitsetSet = (0..IDS).collect();
+        bitset.shrink_to_fit();
+        let hashset: HashSet<u64> = (0..IDS).collect();
+        assert_eq!(bitset.len(), hashset.len());
+
+        // A bit per identifier versus (at least) a whole `u64` per identifier
+        let bitset_size: usize = bitset.heap_size();
+        let hashset_size: usize = hashset.capacity() * std::mem::size_of::<u64>();
+        assert_eq!(bitset_size, (IDS as usize).div_ceil(64) * std::mem::size_of::<u64>());
+        assert!(bitset_size * 60 < hashset_size, "Expected BitsetSet ({bitset_size} bytes) to be much smaller than HashSet ({hashset_size} bytes)");
+    }
+}
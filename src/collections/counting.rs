@@ -0,0 +1,186 @@
+//  COUNTING.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:30:29
+//  Last edited:
+//    15 Oct 2026, 14:55:52
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a wrapper around [`SetAsync`]s that keeps track of how much
+//!   is sent through them.
+//
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::Hash;
+
+use super::Recipient;
+use super::set::{Set, SetAsync};
+use crate::messages::Message;
+
+
+/***** AUXILLARY *****/
+/// Describes the communication cost accumulated by a [`Counting`] set.
+///
+/// # Generics
+/// - `I`: The (owned) type of agent identifiers.
+#[derive(Clone)]
+pub struct GossipStats<I> {
+    /// The total number of messages sent.
+    pub messages_sent: usize,
+    /// The total number of payload bytes sent.
+    pub bytes_sent:    usize,
+    /// The number of messages sent per recipient.
+    pub by_recipient:  HashMap<Recipient<I>, usize>,
+}
+
+// Constructors
+impl<I> Default for GossipStats<I> {
+    #[inline]
+    fn default() -> Self { Self { messages_sent: 0, bytes_sent: 0, by_recipient: HashMap::new() } }
+}
+
+// Ops
+impl<I: Debug> Debug for GossipStats<I> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { messages_sent, bytes_sent, by_recipient } = self;
+        let mut fmt = f.debug_struct("GossipStats");
+        fmt.field("messages_sent", messages_sent);
+        fmt.field("bytes_sent", bytes_sent);
+        fmt.field("by_recipient", by_recipient);
+        fmt.finish()
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a [`SetAsync`] such that every message sent through it is counted.
+///
+/// This can be used to study the communication cost of agents. Use it to wrap the statements in a
+/// [`View`](crate::actors::View), after which its [`View::stats()`](crate::actors::View::stats())
+/// reports how much the agent [stated](crate::actors::View::state()) and
+/// [gossiped](crate::actors::View::gossip()). Bytes are counted as the length of the
+/// [payload](Message::payload()) of every message.
+///
+/// Messages are only counted if the wrapped set accepted them.
+///
+/// Note that [stating](crate::actors::View::state()) a message also goes through this set, as a
+/// message sent to [`Recipient::One`] of the stating agent itself. Hence, statements are counted
+/// too, and show up under the agent's own identifier in [`GossipStats::by_recipient`].
+///
+/// # Generics
+/// - `S`: The wrapped [`SetAsync`].
+/// - `I`: The type of agent identifiers.
+pub struct Counting<S, I: ?Sized + ToOwned> {
+    /// The wrapped set.
+    inner: S,
+    /// The statistics accumulated so far.
+    stats: GossipStats<I::Owned>,
+}
+
+// Constructors
+impl<S, I: ?Sized + ToOwned> Counting<S, I> {
+    /// Constructor for the Counting that initializes it with zero counts.
+    ///
+    /// # Arguments
+    /// - `inner`: The set to wrap.
+    ///
+    /// # Returns
+    /// A new Counting that wraps `inner`.
+    #[inline]
+    pub fn new(inner: S) -> Self { Self { inner, stats: GossipStats::default() } }
+}
+
+// Ops
+impl<S: Debug, I: ?Sized + ToOwned> Debug for Counting<S, I>
+where
+    I::Owned: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { inner, stats } = self;
+        let mut fmt = f.debug_struct("Counting");
+        fmt.field("inner", inner);
+        fmt.field("stats", stats);
+        fmt.finish()
+    }
+}
+
+// Counting
+impl<S, I: ?Sized + ToOwned> Counting<S, I> {
+    /// Returns the statistics accumulated so far.
+    ///
+    /// # Returns
+    /// A reference to the [`GossipStats`] of this set.
+    #[inline]
+    pub const fn stats(&self) -> &GossipStats<I::Owned> { &self.stats }
+
+    /// Resets the statistics to zero.
+    ///
+    /// # Returns
+    /// The [`GossipStats`] accumulated before resetting.
+    #[inline]
+    pub fn reset(&mut self) -> GossipStats<I::Owned> { std::mem::take(&mut self.stats) }
+
+    /// Returns the wrapped set.
+    ///
+    /// # Returns
+    /// A reference to the wrapped set.
+    #[inline]
+    pub const fn inner(&self) -> &S { &self.inner }
+
+    /// Returns the wrapped set.
+    ///
+    /// # Returns
+    /// The wrapped set, discarding the statistics.
+    #[inline]
+    pub fn into_inner(self) -> S { self.inner }
+}
+
+// Collections
+impl<S, I, E> Set<E> for Counting<S, I>
+where
+    S: Set<E>,
+    I: ?Sized + ToOwned,
+{
+    type Error = S::Error;
+
+    #[inline]
+    fn get(&self, elem: &E) -> Result<Option<&E>, Self::Error> { self.inner.get(elem) }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's,
+    {
+        self.inner.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.inner.len() }
+}
+impl<S, I, E> SetAsync<I, E> for Counting<S, I>
+where
+    S: SetAsync<I, E>,
+    I: ?Sized + ToOwned,
+    I::Owned: Clone + Eq + Hash,
+    E: Message,
+    E::Payload: AsRef<[u8]>,
+{
+    #[inline]
+    fn add(&mut self, selector: Recipient<I::Owned>, elem: E) -> Result<(), Self::Error> {
+        let bytes: usize = elem.payload().as_ref().len();
+        self.inner.add(selector.clone(), elem)?;
+        self.stats.messages_sent += 1;
+        self.stats.bytes_sent += bytes;
+        *self.stats.by_recipient.entry(selector).or_default() += 1;
+        Ok(())
+    }
+}
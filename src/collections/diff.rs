@@ -0,0 +1,173 @@
+//  DIFF.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:23:50
+//  Last edited:
+//    15 Oct 2026, 16:14:54
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines structural diffs between two [`Map`]s.
+//
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use super::map::Map;
+use crate::auxillary::Identifiable;
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from [`diff()`].
+#[derive(Debug)]
+pub enum DiffError<EO, EN> {
+    /// Failed to access the old map.
+    Old(EO),
+    /// Failed to access the new map.
+    New(EN),
+}
+impl<EO: Display, EN: Display> Display for DiffError<EO, EN> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::Old(err) => err.fmt(f),
+            Self::New(err) => err.fmt(f),
+        }
+    }
+}
+impl<EO: Error, EN: Error> Error for DiffError<EO, EN> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn 'static + Error)> {
+        match self {
+            Self::Old(err) => err.source(),
+            Self::New(err) => err.source(),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes what changed between two [`Map`]s.
+///
+/// See [`diff()`] for more information.
+#[derive(Debug)]
+pub struct MapDiff<'a, E> {
+    /// The elements in the new map whose ID did not occur in the old one.
+    pub added:   Vec<&'a E>,
+    /// The elements in the old map whose ID does not occur in the new one.
+    pub removed: Vec<&'a E>,
+    /// The elements whose ID occurs in both maps, but that are different. Given as `(old, new)`.
+    pub changed: Vec<(&'a E, &'a E)>,
+}
+
+// Ops
+impl<'a, E> Clone for MapDiff<'a, E> {
+    #[inline]
+    fn clone(&self) -> Self { Self { added: self.added.clone(), removed: self.removed.clone(), changed: self.changed.clone() } }
+}
+
+// MapDiff
+impl<'a, E> MapDiff<'a, E> {
+    /// Checks whether anything changed at all.
+    ///
+    /// # Returns
+    /// True if no elements were added, removed or changed, false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() }
+}
+
+
+
+/// Computes the structural difference between two [`Map`]s.
+///
+/// Elements are matched by their [`Identifiable::id()`]. Elements with the same ID in both maps
+/// are then compared using [`PartialEq`] to find whether they changed.
+///
+/// # Arguments
+/// - `old`: The map representing the old state.
+/// - `new`: The map representing the new state.
+///
+/// # Returns
+/// A [`MapDiff`] describing which elements were added, removed and changed. Within each list, the
+/// order of elements is the order in which the maps yield them.
+///
+/// # Errors
+/// This function errors if either of the maps failed to be accessed.
+pub fn diff<'a, E, O, N>(old: &'a O, new: &'a N) -> Result<MapDiff<'a, E>, DiffError<O::Error, N::Error>>
+where
+    E: Identifiable + PartialEq,
+    O: Map<E>,
+    N: Map<E>,
+{
+    let mut res: MapDiff<'a, E> = MapDiff { added: Vec::new(), removed: Vec::new(), changed: Vec::new() };
+    for elem in old.iter().map_err(DiffError::Old)? {
+        match new.get(elem.id()).map_err(DiffError::New)? {
+            Some(other) if other != elem => res.changed.push((elem, other)),
+            Some(_) => continue,
+            None => res.removed.push(elem),
+        }
+    }
+    for elem in new.iter().map_err(DiffError::New)? {
+        if !old.contains_key(elem.id()).map_err(DiffError::Old)? {
+            res.added.push(elem);
+        }
+    }
+    Ok(res)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+
+    /// Minimal element with an identifier.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct Elem {
+        id:   u32,
+        name: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+
+    /// Shorthand for creating a map of [`Elem`]s.
+    fn map(elems: &[(u32, &'static str)]) -> HashMap<u32, Elem> { elems.iter().map(|(id, name)| (*id, Elem { id: *id, name })).collect() }
+
+
+    #[test]
+    fn test_diff() {
+        let old: HashMap<u32, Elem> = map(&[(1, "amy"), (2, "bob"), (3, "cho")]);
+        let new: HashMap<u32, Elem> = map(&[(1, "amy"), (3, "cyd"), (4, "dan")]);
+        let delta: MapDiff<Elem> = diff(&old, &new).unwrap();
+        assert!(!delta.is_empty());
+        assert_eq!(delta.added, vec![&Elem { id: 4, name: "dan" }]);
+        assert_eq!(delta.removed, vec![&Elem { id: 2, name: "bob" }]);
+        assert_eq!(delta.changed, vec![(&Elem { id: 3, name: "cho" }, &Elem { id: 3, name: "cyd" })]);
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let old: HashMap<u32, Elem> = map(&[(1, "amy"), (2, "bob")]);
+        let new: HashMap<u32, Elem> = old.clone();
+        assert!(diff(&old, &new).unwrap().is_empty());
+
+        // Also for empty maps
+        let empty: HashMap<u32, Elem> = HashMap::new();
+        assert!(diff(&empty, &empty).unwrap().is_empty());
+    }
+}
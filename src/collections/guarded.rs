@@ -0,0 +1,297 @@
+//  GUARDED.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:20:08
+//  Last edited:
+//    15 Oct 2026, 15:15:41
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a wrapper around [`SetAsync`]s that only allows one author to
+//!   write to them.
+//
+
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+
+use super::Recipient;
+use super::set::{Set, SetAsync};
+use crate::auxillary::{Actored, Authored};
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`AuthorGuarded`] set.
+#[derive(Debug)]
+pub enum GuardError<I, E> {
+    /// Someone attempted to add an element not written by the allowed author.
+    IllegalAuthor { allowed: I, author: I },
+    /// The wrapped set failed.
+    Set(E),
+}
+impl<I: Debug, E: Display> Display for GuardError<I, E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::IllegalAuthor { allowed, author } => write!(f, "Only {allowed:?} is allowed to add elements to this set, not {author:?}"),
+            Self::Set(err) => err.fmt(f),
+        }
+    }
+}
+impl<I: Debug, E: Error> Error for GuardError<I, E> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn 'static + Error)> {
+        match self {
+            Self::IllegalAuthor { .. } => None,
+            Self::Set(err) => err.source(),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a [`SetAsync`] such that only elements by one particular author can be added to it.
+///
+/// This makes the check that agents may only state their own messages (or enact their own
+/// actions) reusable for any set. Whose element something is is determined by a function given
+/// upon construction; see [`AuthorGuarded::for_messages()`] and [`AuthorGuarded::for_actions()`]
+/// for the common cases.
+///
+/// # Generics
+/// - `S`: The wrapped [`SetAsync`].
+/// - `I`: The type of author identifiers.
+/// - `E`: The type of elements in the set.
+pub struct AuthorGuarded<S, I: ?Sized + ToOwned, E> {
+    /// The wrapped set.
+    set:       S,
+    /// The only author allowed to add elements.
+    allowed:   I::Owned,
+    /// Finds the author of an element.
+    author_of: fn(&E) -> &I,
+}
+
+// Constructors
+impl<S, I: ?Sized + ToOwned, E> AuthorGuarded<S, I, E> {
+    /// Constructor for the AuthorGuarded set.
+    ///
+    /// # Arguments
+    /// - `set`: The [`SetAsync`] to wrap.
+    /// - `allowed`: The identifier of the only author allowed to add elements.
+    /// - `author_of`: A function that returns the author of a given element.
+    ///
+    /// # Returns
+    /// A new AuthorGuarded that wraps `set`.
+    #[inline]
+    pub const fn new(set: S, allowed: I::Owned, author_of: fn(&E) -> &I) -> Self { Self { set, allowed, author_of } }
+
+    /// Constructor for the AuthorGuarded set that guards a set of [`Authored`] elements (e.g.,
+    /// [`Message`](crate::messages::Message)s).
+    ///
+    /// # Arguments
+    /// - `set`: The [`SetAsync`] to wrap.
+    /// - `allowed`: The identifier of the only author allowed to add elements.
+    ///
+    /// # Returns
+    /// A new AuthorGuarded that wraps `set`.
+    #[inline]
+    pub fn for_messages(set: S, allowed: I::Owned) -> Self
+    where
+        E: Authored<AuthorId = I>,
+    {
+        Self::new(set, allowed, E::author_id)
+    }
+
+    /// Constructor for the AuthorGuarded set that guards a set of [`Actored`] elements (e.g.,
+    /// [`Action`](crate::actions::Action)s).
+    ///
+    /// # Arguments
+    /// - `set`: The [`SetAsync`] to wrap.
+    /// - `allowed`: The identifier of the only actor allowed to add elements.
+    ///
+    /// # Returns
+    /// A new AuthorGuarded that wraps `set`.
+    #[inline]
+    pub fn for_actions(set: S, allowed: I::Owned) -> Self
+    where
+        E: Actored<ActorId = I>,
+    {
+        Self::new(set, allowed, E::actor_id)
+    }
+}
+
+// Ops
+impl<S: Debug, I: ?Sized + ToOwned, E> Debug for AuthorGuarded<S, I, E>
+where
+    I::Owned: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { set, allowed, author_of: _ } = self;
+        let mut fmt = f.debug_struct("AuthorGuarded");
+        fmt.field("set", set);
+        fmt.field("allowed", allowed);
+        fmt.field("author_of", &"<function>");
+        fmt.finish()
+    }
+}
+
+// AuthorGuarded
+impl<S, I: ?Sized + ToOwned, E> AuthorGuarded<S, I, E> {
+    /// Returns the identifier of the only author allowed to add elements.
+    ///
+    /// # Returns
+    /// A reference to the allowed author's `I`dentifier.
+    #[inline]
+    pub fn allowed(&self) -> &I { self.allowed.borrow() }
+
+    /// Returns the wrapped set.
+    ///
+    /// # Returns
+    /// A reference to the internal `S`.
+    #[inline]
+    pub const fn inner(&self) -> &S { &self.set }
+
+    /// Returns the wrapped set, discarding the guard.
+    ///
+    /// # Returns
+    /// The internal `S`.
+    #[inline]
+    pub fn into_inner(self) -> S { self.set }
+}
+
+// Collections
+impl<S, I, E> Set<E> for AuthorGuarded<S, I, E>
+where
+    S: Set<E>,
+    I: ?Sized + ToOwned,
+    I::Owned: 'static + Send + Debug,
+{
+    type Error = GuardError<I::Owned, S::Error>;
+
+    #[inline]
+    fn contains(&self, elem: &E) -> Result<bool, Self::Error> { self.set.contains(elem).map_err(GuardError::Set) }
+
+    #[inline]
+    fn get(&self, elem: &E) -> Result<Option<&E>, Self::Error> { self.set.get(elem).map_err(GuardError::Set) }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's,
+    {
+        self.set.iter().map_err(GuardError::Set)
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.set.len().map_err(GuardError::Set) }
+}
+impl<S, I, E> SetAsync<I, E> for AuthorGuarded<S, I, E>
+where
+    S: SetAsync<I, E>,
+    I: ?Sized + ToOwned + PartialEq,
+    I::Owned: 'static + Clone + Send + Debug,
+{
+    #[inline]
+    fn add(&mut self, selector: Recipient<I::Owned>, elem: E) -> Result<(), Self::Error> {
+        let author: &I = (self.author_of)(&elem);
+        if author != self.allowed.borrow() {
+            return Err(GuardError::IllegalAuthor { allowed: self.allowed.clone(), author: author.to_owned() });
+        }
+        self.set.add(selector, elem).map_err(GuardError::Set)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+
+    /// Minimal message that is just an author and a textual payload.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestMessage {
+        author_id: String,
+        payload:   String,
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+
+    /// Shorthand for creating a [`TestMessage`].
+    fn msg(author_id: &str, payload: &str) -> TestMessage { TestMessage { author_id: author_id.into(), payload: payload.into() } }
+
+    /// An asynchronous set that remembers every message sent through it, and to who.
+    #[derive(Debug, Default)]
+    struct Outbox {
+        sent: Vec<(Recipient<String>, TestMessage)>,
+    }
+    impl Set<TestMessage> for Outbox {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &TestMessage) -> Result<Option<&TestMessage>, Self::Error> {
+            Ok(<[(Recipient<String>, TestMessage)]>::iter(&self.sent).map(|(_, e)| e).find(|e| *e == elem))
+        }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s TestMessage>, Self::Error>
+        where
+            TestMessage: 's,
+        {
+            Ok(<[(Recipient<String>, TestMessage)]>::iter(&self.sent).map(|(_, e)| e))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.sent.len()) }
+    }
+    impl SetAsync<str, TestMessage> for Outbox {
+        #[inline]
+        fn add(&mut self, selector: Recipient<String>, elem: TestMessage) -> Result<(), Self::Error> {
+            self.sent.push((selector, elem));
+            Ok(())
+        }
+    }
+
+
+    #[test]
+    fn test_author_guarded() {
+        let mut set: AuthorGuarded<Outbox, str, TestMessage> = AuthorGuarded::for_messages(Outbox::default(), "amy".into());
+        assert_eq!(set.allowed(), "amy");
+
+        // Own messages are passed on
+        set.add(Recipient::All, msg("amy", "foo.")).unwrap();
+        set.add(Recipient::One("bob".into()), msg("amy", "bar.")).unwrap();
+        assert_eq!(set.len().unwrap(), 2);
+        assert_eq!(set.inner().sent, vec![(Recipient::All, msg("amy", "foo.")), (Recipient::One("bob".into()), msg("amy", "bar."))]);
+    }
+
+    #[test]
+    fn test_author_guarded_foreign() {
+        let mut set: AuthorGuarded<Outbox, str, TestMessage> = AuthorGuarded::for_messages(Outbox::default(), "amy".into());
+        set.add(Recipient::All, msg("amy", "foo.")).unwrap();
+
+        // Someone else's messages are refused, and never reach the inner set
+        match set.add(Recipient::All, msg("bob", "bar.")) {
+            Err(GuardError::IllegalAuthor { allowed, author }) => {
+                assert_eq!(allowed, "amy");
+                assert_eq!(author, "bob");
+            },
+            res => panic!("Expected an illegal author error, got {res:?}"),
+        }
+        assert_eq!(set.into_inner().sent, vec![(Recipient::All, msg("amy", "foo."))]);
+    }
+}
@@ -0,0 +1,212 @@
+//  IMMUTABLE.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:23:17
+//  Last edited:
+//    15 Oct 2026, 16:14:35
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a wrapper around [`MapSync`]s that refuses to overwrite
+//!   elements (first-writer-wins).
+//
+
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+
+use super::map::{Map, MapSync};
+use crate::auxillary::{Identifiable, IdentifiableMut};
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from the [`ImmutableMap`].
+#[derive(Debug)]
+pub enum ImmutableError<I, E> {
+    /// Attempted to add an element with an ID that already exists.
+    AlreadyExists { id: I },
+    /// The wrapped map failed.
+    Map(E),
+}
+impl<I: Debug, E: Display> Display for ImmutableError<I, E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::AlreadyExists { id } => write!(f, "An element with ID {id:?} already exists and cannot be overwritten"),
+            Self::Map(err) => err.fmt(f),
+        }
+    }
+}
+impl<I: Debug, E: Error> Error for ImmutableError<I, E> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn 'static + Error)> {
+        match self {
+            Self::AlreadyExists { .. } => None,
+            Self::Map(err) => err.source(),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a [`MapSync`] such that elements, once added, cannot be overwritten.
+///
+/// By default, [`MapSync::add()`] overwrites existing elements with the same ID (i.e.,
+/// last-writer-wins). Some protocols require that once an ID is taken, its element is immutable
+/// (i.e., first-writer-wins). This prevents, e.g., an agent from equivocating by restating a
+/// different payload under the same ID.
+///
+/// Note that elements can still be [removed](MapSync::remove()), after which their ID can be
+/// reused.
+///
+/// # Generics
+/// - `M`: The wrapped [`MapSync`].
+#[derive(Clone, Copy, Debug)]
+pub struct ImmutableMap<M>(pub M);
+
+// Collections
+impl<M, E> Map<E> for ImmutableMap<M>
+where
+    M: Map<E>,
+    E: Identifiable,
+    E::Id: ToOwned,
+    <E::Id as ToOwned>::Owned: 'static + Send + Debug,
+{
+    type Error = ImmutableError<<E::Id as ToOwned>::Owned, M::Error>;
+
+    #[inline]
+    fn contains_key(&self, id: &<E as Identifiable>::Id) -> Result<bool, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.0.contains_key(id).map_err(ImmutableError::Map)
+    }
+
+    #[inline]
+    fn get(&self, id: &<E as Identifiable>::Id) -> Result<Option<&E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.0.get(id).map_err(ImmutableError::Map)
+    }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's + Identifiable,
+    {
+        self.0.iter().map_err(ImmutableError::Map)
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.0.len().map_err(ImmutableError::Map) }
+}
+impl<M, E> MapSync<E> for ImmutableMap<M>
+where
+    M: MapSync<E>,
+    E: Identifiable,
+    E::Id: ToOwned,
+    <E::Id as ToOwned>::Owned: 'static + Send + Debug,
+{
+    /// Adds a new element to the map.
+    ///
+    /// Unlike for most maps, this fails if an element with the same ID already exists.
+    ///
+    /// # Returns
+    /// Always [`None`], as nothing is ever overwritten.
+    ///
+    /// # Errors
+    /// This function errors with [`ImmutableError::AlreadyExists`] if an element with the same ID
+    /// was already in the map, or if the wrapped map failed.
+    #[inline]
+    fn add(&mut self, elem: E) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        if self.0.contains_key(elem.id()).map_err(ImmutableError::Map)? {
+            return Err(ImmutableError::AlreadyExists { id: elem.id().to_owned() });
+        }
+        self.0.add(elem).map_err(ImmutableError::Map)
+    }
+
+    /// Adds a new, default element with the given ID and returns it.
+    ///
+    /// Unlike for most maps, this fails if an element with the given ID already exists, as
+    /// returning it mutably would allow it to be changed.
+    ///
+    /// # Errors
+    /// This function errors with [`ImmutableError::AlreadyExists`] if an element with the given ID
+    /// was already in the map, or if the wrapped map failed.
+    #[inline]
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> Result<&mut E, Self::Error>
+    where
+        E: Default + IdentifiableMut,
+    {
+        if self.0.contains_key(id).map_err(ImmutableError::Map)? {
+            return Err(ImmutableError::AlreadyExists { id: id.to_owned() });
+        }
+        self.0.get_or_default(id).map_err(ImmutableError::Map)
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.0.remove(id).map_err(ImmutableError::Map)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+
+    /// Minimal element with an identifier.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct Elem {
+        id:   u32,
+        name: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+
+
+    #[test]
+    fn test_immutable_map_add() {
+        let mut map: ImmutableMap<HashMap<u32, Elem>> = ImmutableMap(HashMap::new());
+        assert!(map.add(Elem { id: 1, name: "amy" }).unwrap().is_none());
+        assert!(map.add(Elem { id: 2, name: "bob" }).unwrap().is_none());
+
+        // The second add with the same ID fails and keeps the original
+        assert!(matches!(map.add(Elem { id: 1, name: "eve" }), Err(ImmutableError::AlreadyExists { id: 1 })));
+        assert_eq!(Map::get(&map, &1).unwrap(), Some(&Elem { id: 1, name: "amy" }));
+        assert_eq!(Map::len(&map).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_immutable_map_remove() {
+        let mut map: ImmutableMap<HashMap<u32, Elem>> = ImmutableMap(HashMap::new());
+        map.add(Elem { id: 1, name: "amy" }).unwrap();
+        assert_eq!(map.remove(&1).unwrap(), Some(Elem { id: 1, name: "amy" }));
+
+        // The ID can be reused now
+        assert!(map.add(Elem { id: 1, name: "bob" }).unwrap().is_none());
+        assert_eq!(Map::get(&map, &1).unwrap(), Some(&Elem { id: 1, name: "bob" }));
+    }
+}
@@ -0,0 +1,196 @@
+//  INTERNER.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:22:46
+//  Last edited:
+//    15 Oct 2026, 16:14:23
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines an interner that stores every distinct element once, handing
+//!   out shared handles to it.
+//
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::auxillary::Identifiable;
+
+
+/***** LIBRARY *****/
+/// Stores every distinct element (e.g., a [`Message`](crate::messages::Message)) only once, and
+/// hands out cheap [`Arc`]-handles to it.
+///
+/// This is useful when the same element is stored in many places, e.g., when a message is stated
+/// to all agents and ends up in every agent's view. By storing the handles instead of clones,
+/// every place refers to the same physical element. Since [`Message`](crate::messages::Message)
+/// (and friends) are implemented for [`Arc`]s, the handles can be used as elements directly.
+///
+/// Elements are considered the same if they have the same [`Identifiable::id()`].
+///
+/// # Generics
+/// - `I`: The (owned) type of the identifiers of the elements.
+/// - `M`: The type of elements interned.
+#[derive(Clone, Debug)]
+pub struct Interner<I, M> {
+    /// The interned elements, by ID.
+    elems: HashMap<I, Arc<M>>,
+}
+
+// Constructors
+impl<I, M> Default for Interner<I, M> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<I, M> Interner<I, M> {
+    /// Constructor for the Interner that initializes it as empty.
+    ///
+    /// # Returns
+    /// A new Interner that hasn't interned any elements yet.
+    #[inline]
+    pub fn new() -> Self { Self { elems: HashMap::new() } }
+}
+
+// Interner
+impl<I, M> Interner<I, M>
+where
+    M: Identifiable,
+    M::Id: ToOwned<Owned = I>,
+    I: Borrow<M::Id> + Eq + Hash,
+{
+    /// Interns an element.
+    ///
+    /// If an element with the same ID was already interned, `elem` is discarded and a handle to
+    /// the existing one is returned instead.
+    ///
+    /// # Arguments
+    /// - `elem`: The element to intern.
+    ///
+    /// # Returns
+    /// An [`Arc`]-handle to the interned element.
+    #[inline]
+    pub fn intern(&mut self, elem: M) -> Arc<M> {
+        if let Some(existing) = self.elems.get(elem.id()) {
+            return existing.clone();
+        }
+        let elem: Arc<M> = Arc::new(elem);
+        self.elems.insert(elem.id().to_owned(), elem.clone());
+        elem
+    }
+
+    /// Returns a handle to an already interned element.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to find.
+    ///
+    /// # Returns
+    /// An [`Arc`]-handle to the interned element, or [`None`] if no element with that ID was
+    /// interned.
+    #[inline]
+    pub fn get(&self, id: &M::Id) -> Option<Arc<M>> { self.elems.get(id).cloned() }
+
+    /// Forgets all elements that are not referred to outside of this interner anymore.
+    ///
+    /// # Returns
+    /// The number of elements forgotten.
+    #[inline]
+    pub fn prune(&mut self) -> usize {
+        let len: usize = self.elems.len();
+        self.elems.retain(|_, elem| Arc::strong_count(elem) > 1);
+        len - self.elems.len()
+    }
+}
+impl<I, M> Interner<I, M> {
+    /// Returns the number of distinct elements interned.
+    ///
+    /// # Returns
+    /// The number of elements in this interner.
+    #[inline]
+    pub fn len(&self) -> usize { self.elems.len() }
+
+    /// Returns whether any elements were interned.
+    ///
+    /// # Returns
+    /// True if this interner is empty, false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.elems.is_empty() }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::map::{Map, MapSync};
+
+
+    /// Minimal message with an identifier.
+    #[derive(Debug, Eq, PartialEq)]
+    struct TestMessage {
+        id:      String,
+        payload: String,
+    }
+    impl Identifiable for TestMessage {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+
+    /// Shorthand for creating a [`TestMessage`].
+    fn msg(id: &str, payload: &str) -> TestMessage { TestMessage { id: id.into(), payload: payload.into() } }
+
+
+    #[test]
+    fn test_interner_shared() {
+        let mut interner: Interner<String, TestMessage> = Interner::new();
+        let handle: Arc<TestMessage> = interner.intern(msg("a", "foo."));
+
+        // Store the handles in two maps
+        let mut amy: HashMap<String, Arc<TestMessage>> = HashMap::new();
+        let mut bob: HashMap<String, Arc<TestMessage>> = HashMap::new();
+        amy.add(handle.clone()).unwrap();
+        bob.add(interner.get("a").unwrap()).unwrap();
+        assert!(Arc::ptr_eq(Map::get(&amy, "a").unwrap().unwrap(), Map::get(&bob, "a").unwrap().unwrap()));
+        assert!(Arc::ptr_eq(Map::get(&amy, "a").unwrap().unwrap(), &handle));
+    }
+
+    #[test]
+    fn test_interner_existing() {
+        let mut interner: Interner<String, TestMessage> = Interner::new();
+        let first: Arc<TestMessage> = interner.intern(msg("a", "foo."));
+        let second: Arc<TestMessage> = interner.intern(msg("a", "bar."));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(second.payload, "foo.");
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn test_interner_prune() {
+        let mut interner: Interner<String, TestMessage> = Interner::new();
+        let kept: Arc<TestMessage> = interner.intern(msg("a", "foo."));
+        drop(interner.intern(msg("b", "bar.")));
+        drop(interner.intern(msg("c", "baz.")));
+        assert_eq!(interner.len(), 3);
+
+        // Only the unreferenced ones are forgotten
+        assert_eq!(interner.prune(), 2);
+        assert_eq!(interner.len(), 1);
+        assert!(Arc::ptr_eq(&interner.get("a").unwrap(), &kept));
+        assert!(interner.get("b").is_none());
+        assert!(interner.get("c").is_none());
+
+        // Nothing left to forget while still referenced
+        assert_eq!(interner.prune(), 0);
+        drop(kept);
+        assert_eq!(interner.prune(), 1);
+        assert!(interner.is_empty());
+    }
+}
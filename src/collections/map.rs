@@ -4,7 +4,7 @@
 //  Created:
 //    13 Jan 2025, 16:23:26
 //  Last edited:
-//    29 Jan 2025, 22:03:14
+//    15 Oct 2026, 15:12:15
 //  Auto updated?
 //    Yes
 //
@@ -13,7 +13,7 @@
 //!   elements.
 //
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::error::Error;
 use std::hash::Hash;
@@ -21,7 +21,7 @@ use std::hash::Hash;
 use auto_traits::pointer_impls;
 
 pub use super::Recipient;
-use crate::auxillary::Identifiable;
+use crate::auxillary::{Identifiable, IdentifiableMut};
 
 
 /***** AUXILLARY *****/
@@ -131,6 +131,41 @@ pub trait InfallibleMapSync<E>: Map<E, Error = Infallible> + MapSync<E> {
     fn add(&mut self, elem: E) -> Option<E>
     where
         E: Identifiable;
+
+    /// Retrieves an element with a particular ID from this map, inserting a default one if it
+    /// didn't exist yet.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to retrieve.
+    ///
+    /// # Returns
+    /// A mutable reference to the (possibly new) [`Map::Elem`] with the given `id`.
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> &mut E
+    where
+        E: Default + IdentifiableMut;
+
+    /// Removes an element from the map.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to remove.
+    ///
+    /// # Returns
+    /// The removed [`Map::Elem`] if an element with `id` existed, or else [`None`].
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Option<E>
+    where
+        E: Identifiable;
+
+    /// Removes all elements with the given identifiers from the map.
+    ///
+    /// # Arguments
+    /// - `ids`: Something yielding the identifiers of the elements to remove.
+    ///
+    /// # Returns
+    /// The number of elements that were actually removed. IDs that did not exist are not counted.
+    fn remove_all<'a>(&mut self, ids: impl IntoIterator<Item = &'a <E as Identifiable>::Id>) -> usize
+    where
+        E: Identifiable,
+        E::Id: 'a;
 }
 impl<E, T: Map<E, Error = Infallible> + MapSync<E>> InfallibleMapSync<E> for T {
     #[inline]
@@ -142,6 +177,37 @@ impl<E, T: Map<E, Error = Infallible> + MapSync<E>> InfallibleMapSync<E> for T {
         // to construct `Infallible`
         unsafe { <T as MapSync<E>>::add(self, elem).unwrap_unchecked() }
     }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> &mut E
+    where
+        E: Default + IdentifiableMut,
+    {
+        // SAFETY: It is physically impossible for users to express `Err(...)` due to the inability
+        // to construct `Infallible`
+        unsafe { <T as MapSync<E>>::get_or_default(self, id).unwrap_unchecked() }
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Option<E>
+    where
+        E: Identifiable,
+    {
+        // SAFETY: It is physically impossible for users to express `Err(...)` due to the inability
+        // to construct `Infallible`
+        unsafe { <T as MapSync<E>>::remove(self, id).unwrap_unchecked() }
+    }
+
+    #[inline]
+    fn remove_all<'a>(&mut self, ids: impl IntoIterator<Item = &'a <E as Identifiable>::Id>) -> usize
+    where
+        E: Identifiable,
+        E::Id: 'a,
+    {
+        // SAFETY: It is physically impossible for users to express `Err(...)` due to the inability
+        // to construct `Infallible`
+        unsafe { <T as MapSync<E>>::remove_all(self, ids).unwrap_unchecked() }
+    }
 }
 
 /// Convenience wrapper around [`MapAsync`]s for when they are
@@ -355,6 +421,109 @@ pub trait MapSync<E>: Map<E> {
     fn add(&mut self, elem: E) -> Result<Option<E>, Self::Error>
     where
         E: Identifiable;
+
+    /// Retrieves an element with a particular ID from this map, inserting a default one if it
+    /// didn't exist yet.
+    ///
+    /// The new element is created by calling [`Default::default()`] and then stamping it with `id`
+    /// through [`IdentifiableMut::set_id()`]. As such, the element must accept any ID given to
+    /// it; i.e., after `set_id(id)`, its [`Identifiable::id()`] must equal `id`, or else the map
+    /// will end up storing it under the wrong key.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to retrieve.
+    ///
+    /// # Returns
+    /// A mutable reference to the (possibly new) [`Map::Elem`] with the given `id`.
+    ///
+    /// # Errors
+    /// When this function errors is completely implementation-dependent.
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> Result<&mut E, Self::Error>
+    where
+        E: Default + IdentifiableMut;
+
+    /// Removes an element from the map.
+    ///
+    /// Since this is a synchronized map, be aware that this will remove the element for everyone!
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to remove.
+    ///
+    /// # Returns
+    /// The removed [`Map::Elem`] if an element with `id` existed, or else [`None`].
+    ///
+    /// # Errors
+    /// When this function errors is completely implementation-dependent.
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable;
+
+    /// Removes all elements with the given identifiers from the map.
+    ///
+    /// By default, this simply calls [`MapSync::remove()`] for every ID. However, implementations
+    /// may override it to remove all of them in one go.
+    ///
+    /// # Arguments
+    /// - `ids`: Something yielding the identifiers of the elements to remove.
+    ///
+    /// # Returns
+    /// The number of elements that were actually removed. IDs that did not exist are not counted.
+    ///
+    /// # Errors
+    /// When this function errors is completely implementation-dependent. However, typically, these
+    /// are the same conditions as for [`MapSync::remove()`]. Note that, if it does, some of the
+    /// elements may already have been removed.
+    #[inline]
+    fn remove_all<'a>(&mut self, ids: impl IntoIterator<Item = &'a <E as Identifiable>::Id>) -> Result<usize, Self::Error>
+    where
+        E: Identifiable,
+        E::Id: 'a,
+    {
+        let mut count: usize = 0;
+        for id in ids {
+            if self.remove(id)?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Adds all given elements to the map, or none at all.
+    ///
+    /// The elements are buffered and then added one-by-one. If any of them fails, then all
+    /// elements added so far are [removed](MapSync::remove()) again, and any elements they
+    /// overwrote are restored. Note that this rollback is best-effort: if it fails as well, the
+    /// rollback error is discarded in favour of the original one.
+    ///
+    /// # Arguments
+    /// - `iter`: Something yielding the elements to add.
+    ///
+    /// # Errors
+    /// This function errors if any of the elements failed to be added. In that case, the error is
+    /// returned together with all the (un-applied) elements given in `iter`.
+    fn add_all_atomic<I: IntoIterator<Item = E>>(&mut self, iter: I) -> Result<(), (Self::Error, Vec<E>)>
+    where
+        E: Clone + Identifiable,
+    {
+        let elems: Vec<E> = iter.into_iter().collect();
+        let mut olds: Vec<Option<E>> = Vec::with_capacity(elems.len());
+        for elem in &elems {
+            match self.add(elem.clone()) {
+                Ok(old) => olds.push(old),
+                Err(err) => {
+                    // Undo in reverse order, such that duplicate IDs in `elems` are restored correctly
+                    for (elem, old) in <[E]>::iter(&elems).zip(olds).rev() {
+                        let _ = self.remove(elem.id());
+                        if let Some(old) = old {
+                            let _ = self.add(old);
+                        }
+                    }
+                    return Err((err, elems));
+                },
+            }
+        }
+        Ok(())
+    }
 }
 
 // Default impls for std types.
@@ -374,6 +543,44 @@ impl<T: Identifiable> MapSync<T> for Vec<T> {
         self.push(new_elem);
         Ok(None)
     }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<T as Identifiable>::Id) -> Result<&mut T, Self::Error>
+    where
+        T: Default + IdentifiableMut,
+    {
+        match <[T]>::iter(self).position(|elem| elem.id() == id) {
+            Some(i) => Ok(&mut self[i]),
+            None => {
+                let mut elem = T::default();
+                elem.set_id(id);
+                let i: usize = <Vec<T>>::len(self);
+                self.push(elem);
+                Ok(&mut self[i])
+            },
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<T as Identifiable>::Id) -> Result<Option<T>, Self::Error>
+    where
+        T: Identifiable,
+    {
+        Ok(<[T]>::iter(self).position(|elem| elem.id() == id).map(|i| <Vec<T>>::remove(self, i)))
+    }
+
+    #[inline]
+    fn remove_all<'a>(&mut self, ids: impl IntoIterator<Item = &'a <T as Identifiable>::Id>) -> Result<usize, Self::Error>
+    where
+        T: Identifiable,
+        T::Id: 'a,
+    {
+        // Remove them in one pass over the vector instead of one per ID
+        let ids: HashSet<&T::Id> = ids.into_iter().collect();
+        let len: usize = <Vec<T>>::len(self);
+        self.retain(|elem| !ids.contains(elem.id()));
+        Ok(len - <Vec<T>>::len(self))
+    }
 }
 impl<T> MapSync<T> for HashMap<<T::Id as ToOwned>::Owned, T>
 where
@@ -388,6 +595,35 @@ where
     {
         Ok(<Self>::insert(self, elem.id().to_owned(), elem))
     }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<T as Identifiable>::Id) -> Result<&mut T, Self::Error>
+    where
+        T: Default + IdentifiableMut,
+    {
+        Ok(<Self>::entry(self, id.to_owned()).or_insert_with(|| {
+            let mut elem = T::default();
+            elem.set_id(id);
+            elem
+        }))
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<T as Identifiable>::Id) -> Result<Option<T>, Self::Error>
+    where
+        T: Identifiable,
+    {
+        Ok(<Self>::remove(self, id))
+    }
+
+    #[inline]
+    fn remove_all<'a>(&mut self, ids: impl IntoIterator<Item = &'a <T as Identifiable>::Id>) -> Result<usize, Self::Error>
+    where
+        T: Identifiable,
+        T::Id: 'a,
+    {
+        Ok(ids.into_iter().map(|id| <Self>::remove(self, id)).filter(Option::is_some).count())
+    }
 }
 
 
@@ -414,3 +650,172 @@ where
     where
         E: Identifiable;
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter, Result as FResult};
+
+    use super::*;
+    use crate::collections::sorted::SortedVecMap;
+
+
+    /// Test element identified by a number, carrying some value to tell elements with the same ID
+    /// apart.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Elem {
+        id:  u32,
+        val: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl IdentifiableMut for Elem {
+        #[inline]
+        fn set_id(&mut self, id: &Self::Id) { self.id = *id; }
+    }
+
+    /// Shorthand for creating an [`Elem`].
+    fn elem(id: u32, val: &'static str) -> Elem { Elem { id, val } }
+
+    /// Error thrown by the [`FailingMap`].
+    #[derive(Debug)]
+    struct FailError;
+    impl Display for FailError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Refusing to add element") }
+    }
+    impl Error for FailError {}
+
+    /// A [`Vec`]-backed map that refuses to add elements with one particular ID.
+    #[derive(Debug)]
+    struct FailingMap {
+        elems:   Vec<Elem>,
+        fail_on: u32,
+    }
+    impl Map<Elem> for FailingMap {
+        type Error = FailError;
+
+        #[inline]
+        fn get(&self, id: &u32) -> Result<Option<&Elem>, Self::Error> { Ok(Map::get(&self.elems, id).unwrap()) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s Elem>, Self::Error>
+        where
+            Elem: 's,
+        {
+            Ok(<[Elem]>::iter(&self.elems))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.elems.len()) }
+    }
+    impl MapSync<Elem> for FailingMap {
+        #[inline]
+        fn add(&mut self, elem: Elem) -> Result<Option<Elem>, Self::Error> {
+            if elem.id == self.fail_on {
+                return Err(FailError);
+            }
+            Ok(MapSync::add(&mut self.elems, elem).unwrap())
+        }
+
+        #[inline]
+        fn get_or_default(&mut self, id: &u32) -> Result<&mut Elem, Self::Error> { Ok(MapSync::get_or_default(&mut self.elems, id).unwrap()) }
+
+        #[inline]
+        fn remove(&mut self, id: &u32) -> Result<Option<Elem>, Self::Error> { Ok(MapSync::remove(&mut self.elems, id).unwrap()) }
+    }
+
+    /// Returns the elements in a map sorted by ID, for order-independent comparison.
+    fn sorted(map: &FailingMap) -> Vec<Elem> {
+        let mut elems: Vec<Elem> = map.elems.clone();
+        elems.sort_by_key(|e| e.id);
+        elems
+    }
+
+
+    /// Checks [`MapSync::get_or_default()`] on a fresh map of type `M`.
+    fn check_get_or_default<M: Default + MapSync<Elem, Error = Infallible>>() {
+        let mut map: M = M::default();
+        map.add(elem(1, "a")).unwrap();
+
+        // The first access inserts a default element carrying the ID
+        let new: &mut Elem = map.get_or_default(&2).unwrap();
+        assert_eq!(new, &elem(2, ""));
+        new.val = "b";
+        assert_eq!(map.len().unwrap(), 2);
+
+        // The second access returns the same element
+        assert_eq!(map.get_or_default(&2).unwrap(), &elem(2, "b"));
+        assert_eq!(map.get_or_default(&1).unwrap(), &elem(1, "a"));
+        assert_eq!(map.len().unwrap(), 2);
+        assert_eq!(Map::get(&map, &2).unwrap(), Some(&elem(2, "b")));
+    }
+
+    /// Checks [`MapSync::remove_all()`] on a fresh map of type `M`.
+    fn check_remove_all<M: Default + MapSync<Elem, Error = Infallible>>() {
+        let mut map: M = M::default();
+        for id in 1..=5 {
+            map.add(elem(id, "x")).unwrap();
+        }
+
+        // Some IDs are absent, and some are given twice
+        assert_eq!(map.remove_all(&[2, 7, 4, 2, 9, 4]).unwrap(), 2);
+        let mut ids: Vec<u32> = Map::iter(&map).unwrap().map(|e| e.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 3, 5]);
+
+        assert_eq!(map.remove_all(&[]).unwrap(), 0);
+        assert_eq!(map.remove_all(&[2, 4]).unwrap(), 0);
+        assert_eq!(map.remove_all(&[5, 3, 1]).unwrap(), 3);
+        assert_eq!(map.len().unwrap(), 0);
+    }
+
+
+    #[test]
+    fn test_map_get_or_default() {
+        check_get_or_default::<Vec<Elem>>();
+        check_get_or_default::<HashMap<u32, Elem>>();
+        check_get_or_default::<SortedVecMap<Elem>>();
+    }
+
+    #[test]
+    fn test_map_remove_all() {
+        // The batched overrides
+        check_remove_all::<Vec<Elem>>();
+        check_remove_all::<HashMap<u32, Elem>>();
+        // The default implementation
+        check_remove_all::<SortedVecMap<Elem>>();
+    }
+
+    #[test]
+    fn test_map_add_all_atomic() {
+        let mut map: FailingMap = FailingMap { elems: vec![elem(1, "a"), elem(2, "b")], fail_on: 9 };
+        map.add_all_atomic([elem(2, "B"), elem(3, "c")]).unwrap();
+        assert_eq!(sorted(&map), vec![elem(1, "a"), elem(2, "B"), elem(3, "c")]);
+    }
+
+    #[test]
+    fn test_map_add_all_atomic_rollback() {
+        let mut map: FailingMap = FailingMap { elems: vec![elem(1, "a"), elem(2, "b")], fail_on: 9 };
+        let (_, elems): (FailError, Vec<Elem>) = map.add_all_atomic([elem(3, "c"), elem(4, "d"), elem(9, "fail"), elem(5, "e")]).unwrap_err();
+        assert_eq!(elems, vec![elem(3, "c"), elem(4, "d"), elem(9, "fail"), elem(5, "e")]);
+        assert_eq!(sorted(&map), vec![elem(1, "a"), elem(2, "b")]);
+    }
+
+    #[test]
+    fn test_map_add_all_atomic_rollback_overwrites() {
+        // Overwrites existing elements, and adds the same new ID twice
+        let mut map: FailingMap = FailingMap { elems: vec![elem(1, "a"), elem(2, "b")], fail_on: 9 };
+        let res = map.add_all_atomic([elem(2, "B1"), elem(3, "c1"), elem(2, "B2"), elem(3, "c2"), elem(9, "fail")]);
+        assert!(res.is_err());
+        assert_eq!(sorted(&map), vec![elem(1, "a"), elem(2, "b")]);
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    13 Jan 2025, 16:22:05
 //  Last edited:
-//    29 Jan 2025, 15:44:46
+//    15 Oct 2026, 14:45:16
 //  Auto updated?
 //    Yes
 //
@@ -13,8 +13,19 @@
 //
 
 // Declare the modules
+pub mod adaptors;
+pub mod append;
+pub mod bitset;
+pub mod counting;
+pub mod diff;
+pub mod guarded;
+pub mod immutable;
+pub mod interner;
 pub mod map;
+pub mod observable;
 pub mod set;
+pub mod sorted;
+pub mod timestamped;
 
 // Imports
 use std::convert::Infallible;
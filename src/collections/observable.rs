@@ -0,0 +1,406 @@
+//  OBSERVABLE.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:17:51
+//  Last edited:
+//    15 Oct 2026, 15:17:30
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a wrapper around [`SetSync`]s and [`MapSync`]s that notifies
+//!   observers whenever elements are added.
+//
+
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use super::map::{Map, MapSync};
+use super::set::{Set, SetSync};
+use crate::auxillary::{Identifiable, IdentifiableMut};
+
+
+/***** AUXILLARY *****/
+/// The type of callbacks registered in an [`Observable`].
+pub type Callback<E> = Box<dyn Send + Fn(&E)>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a [`SetSync`] or [`MapSync`] such that registered callbacks are invoked whenever an
+/// element is added to it.
+///
+/// This supports event-driven agent designs on top of the poll loop: instead of checking whether a
+/// particular element has appeared every poll, agents can register a callback for it.
+///
+/// Callbacks are called right **after** the new element has been added to the wrapped collection,
+/// with the element as it is stored there. If adding it fails, no callbacks are called. For sets,
+/// callbacks are only called if the element was not present yet. For maps, they are called for
+/// every addition, including ones that replace an element with the same ID (except for
+/// [`MapSync::get_or_default()`], which only calls them when it creates a new element).
+///
+/// # Generics
+/// - `T`: The wrapped collection.
+/// - `E`: The type of elements in the collection.
+pub struct Observable<T, E> {
+    /// The wrapped collection.
+    inner:     T,
+    /// The callbacks to call when an element is added.
+    callbacks: Vec<Callback<E>>,
+}
+
+// Constructors
+impl<T, E> Observable<T, E> {
+    /// Constructor for the Observable that initializes it without any callbacks.
+    ///
+    /// # Arguments
+    /// - `inner`: The collection to wrap.
+    ///
+    /// # Returns
+    /// A new Observable that wraps `inner`.
+    #[inline]
+    pub const fn new(inner: T) -> Self { Self { inner, callbacks: Vec::new() } }
+}
+
+// Ops
+impl<T: Debug, E> Debug for Observable<T, E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { inner, callbacks } = self;
+        let mut fmt = f.debug_struct("Observable");
+        fmt.field("inner", inner);
+        fmt.field("callbacks", &format!("<{} callbacks>", callbacks.len()));
+        fmt.finish()
+    }
+}
+
+// Observable
+impl<T, E> Observable<T, E> {
+    /// Registers a callback that is called whenever any element is added.
+    ///
+    /// # Arguments
+    /// - `callback`: The closure to call with every new element.
+    #[inline]
+    pub fn observe(&mut self, callback: impl 'static + Send + Fn(&E)) { self.callbacks.push(Box::new(callback)); }
+
+    /// Registers a callback that is called whenever an element with a particular ID is added.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to watch for.
+    /// - `callback`: The closure to call with the new element.
+    #[inline]
+    pub fn observe_id(&mut self, id: <E::Id as ToOwned>::Owned, callback: impl 'static + Send + Fn(&E))
+    where
+        E: Identifiable,
+        E::Id: ToOwned,
+        <E::Id as ToOwned>::Owned: 'static + Send,
+    {
+        self.callbacks.push(Box::new(move |elem: &E| {
+            if elem.id() == id.borrow() {
+                callback(elem)
+            }
+        }));
+    }
+
+    /// Calls all callbacks for a new element.
+    ///
+    /// # Arguments
+    /// - `elem`: The element that is added.
+    #[inline]
+    fn notify(&self, elem: &E) {
+        for callback in &self.callbacks {
+            callback(elem);
+        }
+    }
+
+    /// Returns the wrapped collection.
+    ///
+    /// # Returns
+    /// A reference to the internal `T`.
+    #[inline]
+    pub const fn inner(&self) -> &T { &self.inner }
+
+    /// Returns the wrapped collection, discarding the callbacks.
+    ///
+    /// # Returns
+    /// The internal `T`.
+    #[inline]
+    pub fn into_inner(self) -> T { self.inner }
+}
+
+// Collections
+impl<T, E> Set<E> for Observable<T, E>
+where
+    T: Set<E>,
+{
+    type Error = T::Error;
+
+    #[inline]
+    fn contains(&self, elem: &E) -> Result<bool, Self::Error> { self.inner.contains(elem) }
+
+    #[inline]
+    fn get(&self, elem: &E) -> Result<Option<&E>, Self::Error> { self.inner.get(elem) }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's,
+    {
+        self.inner.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.inner.len() }
+}
+impl<T, E> SetSync<E> for Observable<T, E>
+where
+    T: SetSync<E>,
+    E: Clone,
+{
+    #[inline]
+    fn add(&mut self, elem: E) -> Result<bool, Self::Error> {
+        // Keep a copy around to find the stored element back
+        let key: E = elem.clone();
+        let existed: bool = self.inner.add(elem)?;
+        if !existed {
+            if let Some(elem) = self.inner.get(&key)? {
+                self.notify(elem);
+            }
+        }
+        Ok(existed)
+    }
+
+    #[inline]
+    fn clear(&mut self) -> Result<(), Self::Error> { self.inner.clear() }
+}
+
+impl<T, E> Map<E> for Observable<T, E>
+where
+    T: Map<E>,
+{
+    type Error = T::Error;
+
+    #[inline]
+    fn contains_key(&self, id: &<E as Identifiable>::Id) -> Result<bool, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.inner.contains_key(id)
+    }
+
+    #[inline]
+    fn get(&self, id: &<E as Identifiable>::Id) -> Result<Option<&E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.inner.get(id)
+    }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's + Identifiable,
+    {
+        self.inner.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.inner.len() }
+}
+impl<T, E> MapSync<E> for Observable<T, E>
+where
+    T: MapSync<E>,
+    E: Identifiable,
+    E::Id: ToOwned,
+{
+    #[inline]
+    fn add(&mut self, elem: E) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        // Keep the ID around to find the stored element back
+        let id: <E::Id as ToOwned>::Owned = elem.id().to_owned();
+        let old: Option<E> = self.inner.add(elem)?;
+        if let Some(elem) = self.inner.get(id.borrow())? {
+            self.notify(elem);
+        }
+        Ok(old)
+    }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> Result<&mut E, Self::Error>
+    where
+        E: Default + IdentifiableMut,
+    {
+        // Only notify if the element is new
+        let existed: bool = self.inner.contains_key(id)?;
+        let elem: &mut E = self.inner.get_or_default(id)?;
+        if !existed {
+            for callback in &self.callbacks {
+                callback(elem);
+            }
+        }
+        Ok(elem)
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.inner.remove(id)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fmt::Display;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+
+    /// Test element identified by a number, carrying some value to tell elements with the same ID
+    /// apart.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Elem {
+        id:  u32,
+        val: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl IdentifiableMut for Elem {
+        #[inline]
+        fn set_id(&mut self, id: &Self::Id) { self.id = *id; }
+    }
+
+    /// Shorthand for creating an [`Elem`].
+    fn elem(id: u32, val: &'static str) -> Elem { Elem { id, val } }
+
+    /// Error thrown by the [`FailingMap`].
+    #[derive(Debug)]
+    struct FailError;
+    impl Display for FailError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Refusing to add element") }
+    }
+    impl Error for FailError {}
+
+    /// A [`Vec`]-backed map that refuses to add elements with one particular ID.
+    #[derive(Debug)]
+    struct FailingMap {
+        elems:   Vec<Elem>,
+        fail_on: u32,
+    }
+    impl Map<Elem> for FailingMap {
+        type Error = FailError;
+
+        #[inline]
+        fn get(&self, id: &u32) -> Result<Option<&Elem>, Self::Error> { Ok(Map::get(&self.elems, id).unwrap()) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s Elem>, Self::Error>
+        where
+            Elem: 's,
+        {
+            Ok(<[Elem]>::iter(&self.elems))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.elems.len()) }
+    }
+    impl MapSync<Elem> for FailingMap {
+        #[inline]
+        fn add(&mut self, elem: Elem) -> Result<Option<Elem>, Self::Error> {
+            if elem.id == self.fail_on {
+                return Err(FailError);
+            }
+            Ok(MapSync::add(&mut self.elems, elem).unwrap())
+        }
+
+        #[inline]
+        fn get_or_default(&mut self, id: &u32) -> Result<&mut Elem, Self::Error> { Ok(MapSync::get_or_default(&mut self.elems, id).unwrap()) }
+
+        #[inline]
+        fn remove(&mut self, id: &u32) -> Result<Option<Elem>, Self::Error> { Ok(MapSync::remove(&mut self.elems, id).unwrap()) }
+    }
+
+    /// Returns a callback that records every element it's called with, and the record itself.
+    fn recorder() -> (Arc<Mutex<Vec<Elem>>>, impl 'static + Send + Fn(&Elem)) {
+        let log: Arc<Mutex<Vec<Elem>>> = Arc::new(Mutex::new(Vec::new()));
+        let log2: Arc<Mutex<Vec<Elem>>> = log.clone();
+        (log, move |elem: &Elem| log2.lock().unwrap().push(elem.clone()))
+    }
+
+
+    #[test]
+    fn test_observe_id() {
+        let mut map: Observable<Vec<Elem>, Elem> = Observable::new(Vec::new());
+        let (log, callback) = recorder();
+        map.observe_id(2, callback);
+
+        // Only fires for the watched ID, and only once it's there
+        MapSync::add(&mut map, elem(1, "a")).unwrap();
+        MapSync::add(&mut map, elem(3, "c")).unwrap();
+        assert!(log.lock().unwrap().is_empty());
+        MapSync::add(&mut map, elem(2, "b")).unwrap();
+        assert_eq!(*log.lock().unwrap(), vec![elem(2, "b")]);
+        MapSync::add(&mut map, elem(4, "d")).unwrap();
+        MapSync::remove(&mut map, &1).unwrap();
+        assert_eq!(*log.lock().unwrap(), vec![elem(2, "b")]);
+
+        // Replacing the element is an addition too
+        MapSync::add(&mut map, elem(2, "B")).unwrap();
+        assert_eq!(*log.lock().unwrap(), vec![elem(2, "b"), elem(2, "B")]);
+    }
+
+    #[test]
+    fn test_observe_get_or_default() {
+        let mut map: Observable<Vec<Elem>, Elem> = Observable::new(Vec::new());
+        let (log, callback) = recorder();
+        map.observe(callback);
+
+        // Only new elements are reported
+        MapSync::get_or_default(&mut map, &1).unwrap().val = "a";
+        MapSync::get_or_default(&mut map, &1).unwrap();
+        assert_eq!(*log.lock().unwrap(), vec![elem(1, "")]);
+    }
+
+    #[test]
+    fn test_observe_set() {
+        let mut set: Observable<Vec<Elem>, Elem> = Observable::new(Vec::new());
+        let (log, callback) = recorder();
+        set.observe(callback);
+
+        // Elements already in the set are not reported again
+        assert!(!SetSync::add(&mut set, elem(1, "a")).unwrap());
+        assert!(SetSync::add(&mut set, elem(1, "a")).unwrap());
+        assert!(!SetSync::add(&mut set, elem(2, "b")).unwrap());
+        assert_eq!(*log.lock().unwrap(), vec![elem(1, "a"), elem(2, "b")]);
+    }
+
+    #[test]
+    fn test_observe_failed_add() {
+        let mut map: Observable<FailingMap, Elem> = Observable::new(FailingMap { elems: Vec::new(), fail_on: 2 });
+        let (log, callback) = recorder();
+        map.observe_id(2, callback);
+
+        // Failed additions are not reported
+        assert!(matches!(map.add(elem(2, "b")), Err(FailError)));
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(map.inner().elems, vec![]);
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    13 Jan 2025, 16:22:42
 //  Last edited:
-//    29 Jan 2025, 22:03:23
+//    15 Oct 2026, 14:54:38
 //  Auto updated?
 //    Yes
 //
@@ -13,7 +13,7 @@
 //!   identifications.
 //
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::error::Error;
 use std::hash::Hash;
@@ -23,6 +23,36 @@ use auto_traits::pointer_impls;
 pub use super::Recipient;
 
 
+/***** MACROS *****/
+/// Asserts that two [`InfallibleSet`]s contain the same elements, compared by some key.
+///
+/// This is the assertion-version of [`set_eq_by()`]; see it for more information. Both sets must
+/// implement [`Debug`](std::fmt::Debug), such that they can be shown when the assertion fails.
+///
+/// # Arguments
+/// - `lhs`: The first set to compare.
+/// - `rhs`: The second set to compare.
+/// - `key`: A closure that maps elements to the key by which they are compared.
+///
+/// # Panics
+/// This macro panics if the sets do not contain the same elements (according to `key`).
+#[macro_export]
+macro_rules! assert_set_eq {
+    ($lhs:expr, $rhs:expr, $key:expr $(,)?) => {
+        match (&$lhs, &$rhs) {
+            (lhs, rhs) => {
+                if !$crate::collections::set::set_eq_by(lhs, rhs, $key) {
+                    ::std::panic!("assertion `set_eq_by(left, right, key)` failed\n  left: {lhs:?}\n right: {rhs:?}");
+                }
+            },
+        }
+    };
+}
+
+
+
+
+
 /***** AUXILLARY *****/
 /// Convenience wrapper around [`Set`]s for when they are [infallible](std::convert::Infallible).
 pub trait InfallibleSet<E>: Set<E, Error = Infallible> {
@@ -160,6 +190,48 @@ impl<I: ?Sized + ToOwned, E, T: Set<E, Error = Infallible> + SetAsync<I, E>> Inf
 
 
 
+/// Checks whether two [`InfallibleSet`]s contain the same elements, compared by some key.
+///
+/// This is useful for comparing sets of elements that cannot be compared directly (e.g., because
+/// their payloads contain floats). The order in which the sets yield their elements is ignored.
+///
+/// Note that the sets are compared as _multisets_ of keys. Hence, if several elements map to the
+/// same key, both sets must contain equally many of them.
+///
+/// # Arguments
+/// - `lhs`: The first set to compare.
+/// - `rhs`: The second set to compare.
+/// - `key`: A closure that maps elements to the key by which they are compared.
+///
+/// # Returns
+/// True if both sets contain the same keys (equally often), false otherwise.
+pub fn set_eq_by<E, L, R, K, F>(lhs: &L, rhs: &R, mut key: F) -> bool
+where
+    L: InfallibleSet<E>,
+    R: InfallibleSet<E>,
+    K: Eq + Hash,
+    F: FnMut(&E) -> K,
+{
+    if <L as InfallibleSet<E>>::len(lhs) != <R as InfallibleSet<E>>::len(rhs) {
+        return false;
+    }
+
+    // Count the keys on the left, then discount those on the right
+    let mut counts: HashMap<K, usize> = HashMap::new();
+    for elem in <L as InfallibleSet<E>>::iter(lhs) {
+        *counts.entry(key(elem)).or_default() += 1;
+    }
+    for elem in <R as InfallibleSet<E>>::iter(rhs) {
+        match counts.get_mut(&key(elem)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -335,6 +407,41 @@ pub trait SetSync<E>: Set<E> {
     /// # Errors
     /// When this function errors is completely implementation-dependent.
     fn clear(&mut self) -> Result<(), Self::Error>;
+
+    /// Adds all given elements to the set, or none at all.
+    ///
+    /// The elements are buffered and then added one-by-one. If any of them fails, then the set is
+    /// restored to how it was before by [clearing](SetSync::clear()) it and re-adding a copy of
+    /// its original elements. Note that this rollback is best-effort: if it fails as well, the
+    /// rollback error is discarded in favour of the original one.
+    ///
+    /// # Arguments
+    /// - `iter`: Something yielding the elements to add.
+    ///
+    /// # Errors
+    /// This function errors if the set failed to be iterated (to make a copy) or if any of the
+    /// elements failed to be added. In that case, the error is returned together with all the
+    /// (un-applied) elements given in `iter`.
+    fn add_all_atomic<I: IntoIterator<Item = E>>(&mut self, iter: I) -> Result<(), (Self::Error, Vec<E>)>
+    where
+        E: Clone,
+    {
+        let elems: Vec<E> = iter.into_iter().collect();
+        let original: Vec<E> = match self.iter() {
+            Ok(iter) => iter.cloned().collect(),
+            Err(err) => return Err((err, elems)),
+        };
+        for elem in &elems {
+            if let Err(err) = self.add(elem.clone()) {
+                let _ = self.clear();
+                for elem in original {
+                    let _ = self.add(elem);
+                }
+                return Err((err, elems));
+            }
+        }
+        Ok(())
+    }
 }
 
 // Default impls for std types.
@@ -390,3 +497,126 @@ where
     /// When this function errors is completely implementation-dependent.
     fn add(&mut self, selector: Recipient<I::Owned>, elem: E) -> Result<(), Self::Error>;
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter, Result as FResult};
+
+    use super::*;
+
+
+    /// Error thrown by the [`FailingSet`].
+    #[derive(Debug)]
+    struct FailError;
+    impl Display for FailError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Refusing to add element") }
+    }
+    impl Error for FailError {}
+
+    /// A [`Vec`]-backed set that refuses to add one particular element.
+    #[derive(Debug)]
+    struct FailingSet {
+        elems:   Vec<&'static str>,
+        fail_on: &'static str,
+    }
+    impl Set<&'static str> for FailingSet {
+        type Error = FailError;
+
+        #[inline]
+        fn get(&self, elem: &&'static str) -> Result<Option<&&'static str>, Self::Error> { Ok(<[&'static str]>::iter(&self.elems).find(|e| *e == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s &'static str>, Self::Error>
+        where
+            &'static str: 's,
+        {
+            Ok(<[&'static str]>::iter(&self.elems))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.elems.len()) }
+    }
+    impl SetSync<&'static str> for FailingSet {
+        #[inline]
+        fn add(&mut self, elem: &'static str) -> Result<bool, Self::Error> {
+            if elem == self.fail_on {
+                return Err(FailError);
+            }
+            Ok(SetSync::add(&mut self.elems, elem).unwrap())
+        }
+
+        #[inline]
+        fn clear(&mut self) -> Result<(), Self::Error> {
+            self.elems.clear();
+            Ok(())
+        }
+    }
+
+
+    /// Message-like element with a payload that cannot be compared reliably.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Measurement {
+        id:    &'static str,
+        value: f64,
+    }
+
+    /// Shorthand for creating a [`Measurement`].
+    fn measurement(id: &'static str, value: f64) -> Measurement { Measurement { id, value } }
+
+
+    #[test]
+    fn test_set_eq_by() {
+        let lhs: Vec<Measurement> = vec![measurement("a", 1.0), measurement("b", f64::NAN), measurement("c", 0.1 + 0.2)];
+        let rhs: Vec<Measurement> = vec![measurement("c", 0.3), measurement("a", 1.0), measurement("b", f64::NAN)];
+        assert_ne!(lhs[1], rhs[2]);
+        assert!(set_eq_by(&lhs, &rhs, |m| m.id));
+        assert!(!set_eq_by(&lhs, &rhs, |m| m.value.to_bits()));
+        assert!(!set_eq_by(&lhs, &rhs[..2].to_vec(), |m| m.id));
+        assert!(set_eq_by(&Vec::<Measurement>::new(), &Vec::new(), |m| m.id));
+    }
+
+    #[test]
+    fn test_set_eq_by_multiplicity() {
+        let lhs: Vec<Measurement> = vec![measurement("a", 1.0), measurement("a", 2.0), measurement("b", 3.0)];
+        let rhs: Vec<Measurement> = vec![measurement("a", 1.0), measurement("b", 2.0), measurement("b", 3.0)];
+        assert!(!set_eq_by(&lhs, &rhs, |m| m.id));
+        assert!(!set_eq_by(&rhs, &lhs, |m| m.id));
+        assert!(set_eq_by(&lhs, &<[Measurement]>::iter(&lhs).rev().cloned().collect::<Vec<Measurement>>(), |m| m.id));
+    }
+
+    #[test]
+    fn test_assert_set_eq() {
+        let lhs: Vec<Measurement> = vec![measurement("a", f64::NAN), measurement("b", 2.0)];
+        let rhs: Vec<Measurement> = vec![measurement("b", 2.0), measurement("a", f64::NAN)];
+        crate::assert_set_eq!(lhs, rhs, |m: &Measurement| m.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `set_eq_by(left, right, key)` failed")]
+    fn test_assert_set_eq_fails() {
+        let lhs: Vec<Measurement> = vec![measurement("a", 1.0), measurement("a", 1.0), measurement("b", 2.0)];
+        let rhs: Vec<Measurement> = vec![measurement("a", 1.0), measurement("b", 2.0), measurement("b", 2.0)];
+        crate::assert_set_eq!(lhs, rhs, |m: &Measurement| m.id);
+    }
+
+    #[test]
+    fn test_set_add_all_atomic() {
+        let mut set: FailingSet = FailingSet { elems: vec!["x", "y"], fail_on: "fail" };
+        set.add_all_atomic(["a", "b", "c"]).unwrap();
+        assert_eq!(set.elems, vec!["x", "y", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_set_add_all_atomic_rollback() {
+        let mut set: FailingSet = FailingSet { elems: vec!["x", "y"], fail_on: "fail" };
+        let (_, elems): (FailError, Vec<&'static str>) = set.add_all_atomic(["a", "x", "fail", "d"]).unwrap_err();
+        assert_eq!(elems, vec!["a", "x", "fail", "d"]);
+        assert_eq!(set.elems, vec!["x", "y"]);
+    }
+}
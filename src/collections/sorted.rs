@@ -0,0 +1,289 @@
+//  SORTED.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:33:07
+//  Last edited:
+//    15 Oct 2026, 14:51:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a [`Vec`]-backed [`Map`] that keeps its elements sorted by
+//!   ID.
+//
+
+use std::convert::Infallible;
+
+use super::map::{Map, MapSync};
+use crate::auxillary::{Identifiable, IdentifiableMut};
+
+
+/***** LIBRARY *****/
+/// A [`Map`] backed by a [`Vec`] that keeps its elements sorted by their [`Identifiable::id()`].
+///
+/// Unlike the `Map`-implementation for [`Vec`] itself, which searches linearly, this looks up
+/// elements using a binary search (i.e., in O(log n)). The price is that adding or removing
+/// elements shifts the elements after it, which is O(n). As such, this map is best for elements
+/// that are looked up often but changed rarely.
+///
+/// Since the elements are kept in order, they can also be accessed as a sorted slice without
+/// copying (see [`SortedVecMap::as_slice()`]).
+///
+/// # Generics
+/// - `T`: The type of elements stored in the map.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SortedVecMap<T> {
+    /// The elements, sorted by ID.
+    elems: Vec<T>,
+}
+
+// Constructors
+impl<T> Default for SortedVecMap<T> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<T> SortedVecMap<T> {
+    /// Constructor for the SortedVecMap that initializes it as empty.
+    ///
+    /// # Returns
+    /// A new SortedVecMap without any elements in it.
+    #[inline]
+    pub const fn new() -> Self { Self { elems: Vec::new() } }
+
+    /// Constructor for the SortedVecMap that initializes it as empty, but with space for a given
+    /// number of elements.
+    ///
+    /// # Arguments
+    /// - `capacity`: The number of elements to allocate space for.
+    ///
+    /// # Returns
+    /// A new SortedVecMap without any elements in it.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self { Self { elems: Vec::with_capacity(capacity) } }
+}
+impl<T> From<Vec<T>> for SortedVecMap<T>
+where
+    T: Identifiable,
+    T::Id: Ord,
+{
+    /// Sorts the given elements by ID.
+    ///
+    /// If multiple elements have the same ID, only the last one is kept (as if they were all
+    /// [added](MapSync::add()) in order).
+    #[inline]
+    fn from(mut value: Vec<T>) -> Self {
+        // NOTE: The sort is stable, so the last element of every ID ends up last in its run
+        value.sort_by(|lhs, rhs| lhs.id().cmp(rhs.id()));
+        value.reverse();
+        value.dedup_by(|next, prev| next.id() == prev.id());
+        value.reverse();
+        Self { elems: value }
+    }
+}
+impl<T> FromIterator<T> for SortedVecMap<T>
+where
+    T: Identifiable,
+    T::Id: Ord,
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { Self::from(iter.into_iter().collect::<Vec<T>>()) }
+}
+
+// SortedVecMap
+impl<T> SortedVecMap<T> {
+    /// Returns the elements in this map as a slice.
+    ///
+    /// # Returns
+    /// A slice of the elements, sorted by ID.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] { &self.elems }
+
+    /// Returns the elements in this map as a [`Vec`].
+    ///
+    /// # Returns
+    /// The internal vector of elements, sorted by ID.
+    #[inline]
+    pub fn into_inner(self) -> Vec<T> { self.elems }
+}
+impl<T> SortedVecMap<T>
+where
+    T: Identifiable,
+    T::Id: Ord,
+{
+    /// Finds the position of an element with the given ID.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to find.
+    ///
+    /// # Returns
+    /// [`Ok`] with the index of the element if it exists, or [`Err`] with the index at which it
+    /// would have to be inserted otherwise.
+    #[inline]
+    fn search(&self, id: &T::Id) -> Result<usize, usize> { self.elems.binary_search_by(|elem| elem.id().cmp(id)) }
+}
+
+// Collections
+impl<T> Map<T> for SortedVecMap<T>
+where
+    T: Identifiable,
+    T::Id: Ord,
+{
+    type Error = Infallible;
+
+
+    #[inline]
+    fn get(&self, id: &<T as Identifiable>::Id) -> Result<Option<&T>, Self::Error>
+    where
+        T: Identifiable,
+    {
+        Ok(self.search(id).ok().map(|i| &self.elems[i]))
+    }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s T>, Self::Error>
+    where
+        T: 's + Identifiable,
+    {
+        Ok(<[T]>::iter(&self.elems))
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { Ok(self.elems.len()) }
+}
+impl<T> MapSync<T> for SortedVecMap<T>
+where
+    T: Identifiable,
+    T::Id: Ord,
+{
+    #[inline]
+    fn add(&mut self, elem: T) -> Result<Option<T>, Self::Error>
+    where
+        T: Identifiable,
+    {
+        match self.search(elem.id()) {
+            Ok(i) => Ok(Some(std::mem::replace(&mut self.elems[i], elem))),
+            Err(i) => {
+                self.elems.insert(i, elem);
+                Ok(None)
+            },
+        }
+    }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<T as Identifiable>::Id) -> Result<&mut T, Self::Error>
+    where
+        T: Default + IdentifiableMut,
+    {
+        let i: usize = match self.search(id) {
+            Ok(i) => i,
+            Err(i) => {
+                let mut elem = T::default();
+                elem.set_id(id);
+                self.elems.insert(i, elem);
+                i
+            },
+        };
+        Ok(&mut self.elems[i])
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<T as Identifiable>::Id) -> Result<Option<T>, Self::Error>
+    where
+        T: Identifiable,
+    {
+        Ok(self.search(id).ok().map(|i| self.elems.remove(i)))
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Test element identified by a number, carrying some value to tell elements with the same ID
+    /// apart.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Elem {
+        id:  u32,
+        val: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl IdentifiableMut for Elem {
+        #[inline]
+        fn set_id(&mut self, id: &Self::Id) { self.id = *id; }
+    }
+
+    /// Shorthand for creating an [`Elem`].
+    fn elem(id: u32, val: &'static str) -> Elem { Elem { id, val } }
+
+    /// Returns the IDs of the elements in a map, in the order they're iterated.
+    fn ids(map: &SortedVecMap<Elem>) -> Vec<u32> { Map::iter(map).unwrap().map(|e| e.id).collect() }
+
+
+    #[test]
+    fn test_sorted_vec_map_add() {
+        let mut map: SortedVecMap<Elem> = SortedVecMap::new();
+        for id in [5, 1, 4, 2, 3] {
+            assert_eq!(map.add(elem(id, "old")).unwrap(), None);
+        }
+        assert_eq!(ids(&map), vec![1, 2, 3, 4, 5]);
+
+        // Replacing returns the old one
+        assert_eq!(map.add(elem(3, "new")).unwrap(), Some(elem(3, "old")));
+        assert_eq!(ids(&map), vec![1, 2, 3, 4, 5]);
+        assert_eq!(Map::get(&map, &3).unwrap(), Some(&elem(3, "new")));
+    }
+
+    #[test]
+    fn test_sorted_vec_map_get() {
+        let map: SortedVecMap<Elem> = [elem(4, "d"), elem(2, "b"), elem(8, "h")].into_iter().collect();
+        assert_eq!(Map::get(&map, &2).unwrap(), Some(&elem(2, "b")));
+        assert_eq!(Map::get(&map, &4).unwrap(), Some(&elem(4, "d")));
+        assert_eq!(Map::get(&map, &8).unwrap(), Some(&elem(8, "h")));
+        for missing in [0, 3, 5, 9] {
+            assert_eq!(Map::get(&map, &missing).unwrap(), None);
+        }
+        assert_eq!(Map::len(&map).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_sorted_vec_map_get_or_default() {
+        let mut map: SortedVecMap<Elem> = [elem(1, "a"), elem(5, "e")].into_iter().collect();
+        assert_eq!(map.get_or_default(&3).unwrap(), &elem(3, ""));
+        assert_eq!(ids(&map), vec![1, 3, 5]);
+
+        // Existing elements are returned as-is
+        map.get_or_default(&3).unwrap().val = "c";
+        assert_eq!(map.get_or_default(&3).unwrap(), &elem(3, "c"));
+        assert_eq!(map.get_or_default(&5).unwrap(), &elem(5, "e"));
+        assert_eq!(ids(&map), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn test_sorted_vec_map_remove() {
+        let mut map: SortedVecMap<Elem> = [elem(1, "a"), elem(2, "b"), elem(3, "c")].into_iter().collect();
+        assert_eq!(map.remove(&2).unwrap(), Some(elem(2, "b")));
+        assert_eq!(map.remove(&2).unwrap(), None);
+        assert_eq!(map.remove(&7).unwrap(), None);
+        assert_eq!(ids(&map), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_sorted_vec_map_from_vec() {
+        let map: SortedVecMap<Elem> = SortedVecMap::from(vec![elem(3, "c1"), elem(1, "a1"), elem(3, "c2"), elem(2, "b"), elem(1, "a2"), elem(3, "c3")]);
+        assert_eq!(map.as_slice(), &[elem(1, "a2"), elem(2, "b"), elem(3, "c3")]);
+        assert_eq!(ids(&map), vec![1, 2, 3]);
+        assert_eq!(map.into_inner(), vec![elem(1, "a2"), elem(2, "b"), elem(3, "c3")]);
+    }
+}
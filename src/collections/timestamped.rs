@@ -0,0 +1,261 @@
+//  TIMESTAMPED.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:08:45
+//  Last edited:
+//    15 Oct 2026, 16:13:33
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a wrapper around [`MapSync`]s that remembers when every
+//!   element was added.
+//
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::Hash;
+
+use super::map::{Map, MapSync};
+use crate::auxillary::{Identifiable, IdentifiableMut};
+
+
+/***** LIBRARY *****/
+/// Wraps a [`MapSync`] such that it records the time at which every element was added to it.
+///
+/// The time is obtained by calling a user-supplied clock whenever an element is added. This way,
+/// it is up to the user to decide what time means (e.g., a logical round counter or a
+/// wall-clock).
+///
+/// Note that the wrapper only knows about elements added through it. Elements that were already
+/// in the map when it was wrapped do not have a timestamp.
+///
+/// # Generics
+/// - `M`: The wrapped [`MapSync`].
+/// - `I`: The (owned) type of the identifiers of the elements in the map.
+/// - `T`: The type of timestamps produced by the clock.
+pub struct Timestamped<M, I, T> {
+    /// The wrapped map.
+    map:   M,
+    /// The clock producing the current time.
+    clock: Box<dyn Send + FnMut() -> T>,
+    /// The times at which every element was added.
+    added: HashMap<I, T>,
+}
+
+// Constructors
+impl<M, I, T> Timestamped<M, I, T> {
+    /// Constructor for the Timestamped map.
+    ///
+    /// # Arguments
+    /// - `map`: The [`MapSync`] to wrap.
+    /// - `clock`: Some closure that is called to find the current time whenever an element is
+    ///   added.
+    ///
+    /// # Returns
+    /// A new Timestamped that wraps `map`.
+    #[inline]
+    pub fn new(map: M, clock: impl 'static + Send + FnMut() -> T) -> Self { Self { map, clock: Box::new(clock), added: HashMap::new() } }
+}
+
+// Ops
+impl<M: Debug, I: Debug, T: Debug> Debug for Timestamped<M, I, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { map, clock: _, added } = self;
+        let mut fmt = f.debug_struct("Timestamped");
+        fmt.field("map", map);
+        fmt.field("clock", &"<closure>");
+        fmt.field("added", added);
+        fmt.finish()
+    }
+}
+
+// Timestamped
+impl<M, I, T> Timestamped<M, I, T> {
+    /// Returns the time at which the element with the given ID was added to this map.
+    ///
+    /// If the element was added multiple times (i.e., it was overwritten), then the time of the
+    /// latest addition is returned.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the element to find the time of.
+    ///
+    /// # Returns
+    /// The time at which the element was added, or [`None`] if it was never added through this
+    /// wrapper.
+    #[inline]
+    pub fn added_at<Q>(&self, id: &Q) -> Option<&T>
+    where
+        Q: ?Sized + Eq + Hash,
+        I: Borrow<Q> + Eq + Hash,
+    {
+        self.added.get(id)
+    }
+
+    /// Returns the wrapped map.
+    ///
+    /// # Returns
+    /// A reference to the internal `M`.
+    #[inline]
+    pub const fn inner(&self) -> &M { &self.map }
+
+    /// Returns the wrapped map, discarding the timestamps.
+    ///
+    /// # Returns
+    /// The internal `M`.
+    #[inline]
+    pub fn into_inner(self) -> M { self.map }
+}
+
+// Collections
+impl<M, I, T, E> Map<E> for Timestamped<M, I, T>
+where
+    M: Map<E>,
+{
+    type Error = M::Error;
+
+    #[inline]
+    fn contains_key(&self, id: &<E as Identifiable>::Id) -> Result<bool, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.map.contains_key(id)
+    }
+
+    #[inline]
+    fn get(&self, id: &<E as Identifiable>::Id) -> Result<Option<&E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        self.map.get(id)
+    }
+
+    #[inline]
+    fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s E>, Self::Error>
+    where
+        E: 's + Identifiable,
+    {
+        self.map.iter()
+    }
+
+    #[inline]
+    fn len(&self) -> Result<usize, Self::Error> { self.map.len() }
+}
+impl<M, I, T, E> MapSync<E> for Timestamped<M, I, T>
+where
+    M: MapSync<E>,
+    E: Identifiable,
+    E::Id: ToOwned<Owned = I>,
+    I: Borrow<E::Id> + Eq + Hash,
+{
+    #[inline]
+    fn add(&mut self, elem: E) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        let id: I = elem.id().to_owned();
+        let old: Option<E> = self.map.add(elem)?;
+        self.added.insert(id, (self.clock)());
+        Ok(old)
+    }
+
+    #[inline]
+    fn get_or_default(&mut self, id: &<E as Identifiable>::Id) -> Result<&mut E, Self::Error>
+    where
+        E: Default + IdentifiableMut,
+    {
+        let existed: bool = self.map.contains_key(id)?;
+        let elem: &mut E = self.map.get_or_default(id)?;
+        if !existed {
+            self.added.insert(id.to_owned(), (self.clock)());
+        }
+        Ok(elem)
+    }
+
+    #[inline]
+    fn remove(&mut self, id: &<E as Identifiable>::Id) -> Result<Option<E>, Self::Error>
+    where
+        E: Identifiable,
+    {
+        let elem: Option<E> = self.map.remove(id)?;
+        if elem.is_some() {
+            self.added.remove(id);
+        }
+        Ok(elem)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Minimal element with an identifier.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct Elem {
+        id:   u32,
+        name: &'static str,
+    }
+    impl Identifiable for Elem {
+        type Id = u32;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+
+    /// Creates a [`Timestamped`] with a mock clock that increases by 10 on every call.
+    fn timestamped() -> Timestamped<HashMap<u32, Elem>, u32, u64> {
+        let mut now: u64 = 0;
+        Timestamped::new(HashMap::new(), move || {
+            now += 10;
+            now
+        })
+    }
+
+
+    #[test]
+    fn test_timestamped_add() {
+        let mut map = timestamped();
+        assert!(map.add(Elem { id: 1, name: "amy" }).unwrap().is_none());
+        assert!(map.add(Elem { id: 2, name: "bob" }).unwrap().is_none());
+        assert!(map.add(Elem { id: 3, name: "cho" }).unwrap().is_none());
+        assert_eq!(map.added_at(&1), Some(&10));
+        assert_eq!(map.added_at(&2), Some(&20));
+        assert_eq!(map.added_at(&3), Some(&30));
+        assert_eq!(map.added_at(&4), None);
+        assert_eq!(Map::len(&map).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_timestamped_remove() {
+        let mut map = timestamped();
+        map.add(Elem { id: 1, name: "amy" }).unwrap();
+        map.add(Elem { id: 2, name: "bob" }).unwrap();
+        assert_eq!(map.remove(&1).unwrap(), Some(Elem { id: 1, name: "amy" }));
+        assert_eq!(map.added_at(&1), None);
+        assert_eq!(map.added_at(&2), Some(&20));
+
+        // Removing something absent changes nothing
+        assert_eq!(map.remove(&1).unwrap(), None);
+        assert_eq!(map.added_at(&2), Some(&20));
+    }
+
+    #[test]
+    fn test_timestamped_restamp() {
+        let mut map = timestamped();
+        map.add(Elem { id: 1, name: "amy" }).unwrap();
+        map.add(Elem { id: 2, name: "bob" }).unwrap();
+        assert_eq!(map.add(Elem { id: 1, name: "ann" }).unwrap(), Some(Elem { id: 1, name: "amy" }));
+        assert_eq!(map.added_at(&1), Some(&30));
+        assert_eq!(map.added_at(&2), Some(&20));
+        assert_eq!(Map::get(&map, &1).unwrap().map(|e| e.name), Some("ann"));
+    }
+}
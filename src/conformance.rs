@@ -0,0 +1,218 @@
+//  CONFORMANCE.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:18:25
+//  Last edited:
+//    15 Oct 2026, 14:52:11
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a harness that implementors of the framework's constructable
+//!   traits can use to check their implementations are well-behaved.
+//!
+//!   The functions in this module panic if the contract is violated, and
+//!   are intended to be called from implementors' own test suites.
+//
+
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use crate::actions::ConstructableAction;
+use crate::collections::set::InfallibleSet;
+use crate::messages::{ConstructableMessage, MessageSet};
+
+
+/***** LIBRARY *****/
+/// Checks that a [`ConstructableMessage`] returns what it was constructed with.
+///
+/// In particular, asserts that:
+/// - [`Authored::author_id()`](crate::auxillary::Authored::author_id()) returns the given `author_id`;
+/// - [`Message::payload()`](crate::messages::Message::payload()) returns the given `payload`; and
+/// - the message is equal to a [clone](Clone) of itself.
+///
+/// # Generics
+/// - `M`: The [`ConstructableMessage`] to check.
+///
+/// # Arguments
+/// - `author_id`: Some author to construct the message with.
+/// - `payload`: Some payload to construct the message with.
+///
+/// # Panics
+/// This function panics if `M` violates any of the above.
+pub fn check_message_roundtrip<M>(author_id: <M::AuthorId as ToOwned>::Owned, payload: <M::Payload as ToOwned>::Owned)
+where
+    M: ConstructableMessage + Debug,
+    M::AuthorId: Debug + PartialEq + ToOwned,
+    M::Payload: Debug + PartialEq + ToOwned,
+    <M::AuthorId as ToOwned>::Owned: Clone,
+    <M::Payload as ToOwned>::Owned: Clone,
+{
+    let msg: M = M::new(author_id.clone(), payload.clone());
+    assert_eq!(msg.author_id(), author_id.borrow(), "Constructed message does not return the author it was constructed with");
+    assert_eq!(msg.payload(), payload.borrow(), "Constructed message does not return the payload it was constructed with");
+    assert_eq!(msg.clone(), msg, "Constructed message is not equal to a clone of itself");
+}
+
+/// Checks that a [`ConstructableAction`] returns what it was constructed with.
+///
+/// In particular, asserts that:
+/// - [`Actored::actor_id()`](crate::auxillary::Actored::actor_id()) returns the given `actor_id`;
+/// - [`Action::basis()`](crate::actions::Action::basis()) returns the given `basis`;
+/// - [`Action::extra()`](crate::actions::Action::extra()) returns the given `extra`; and
+/// - [`Action::payload()`](crate::actions::Action::payload()) contains both the basis and all
+///   extra messages.
+///
+/// # Generics
+/// - `A`: The [`ConstructableAction`] to check.
+///
+/// # Arguments
+/// - `actor_id`: Some actor to construct the action with.
+/// - `basis`: Some basis to construct the action with.
+/// - `extra`: Some set of additional messages to construct the action with.
+///
+/// # Panics
+/// This function panics if `A` violates any of the above.
+pub fn check_action_roundtrip<A>(actor_id: <A::ActorId as ToOwned>::Owned, basis: A::Message, extra: MessageSet<A::Message>)
+where
+    A: ConstructableAction,
+    A::ActorId: Debug + PartialEq + ToOwned,
+    A::Message: Clone + Debug + Eq + Hash,
+    <A::ActorId as ToOwned>::Owned: Clone,
+{
+    let act: A = A::new(actor_id.clone(), basis.clone(), extra.clone());
+    assert_eq!(act.actor_id(), actor_id.borrow(), "Constructed action does not return the actor it was constructed with");
+    assert_eq!(act.basis(), &basis, "Constructed action does not return the basis it was constructed with");
+    assert_eq!(act.extra(), &extra, "Constructed action does not return the extra messages it was constructed with");
+
+    let payload: MessageSet<A::Message> = act.payload();
+    assert!(payload.contains(&basis), "Payload of constructed action does not contain its basis");
+    for msg in <MessageSet<A::Message> as InfallibleSet<A::Message>>::iter(&extra) {
+        assert!(payload.contains(msg), "Payload of constructed action does not contain extra message {msg:?}");
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Action;
+    use crate::auxillary::{Actored, Authored};
+    use crate::collections::set::SetSync;
+    use crate::messages::Message;
+
+
+    /// Minimal reference message that is just an author and a textual payload.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct RefMessage {
+        author_id: String,
+        payload:   String,
+    }
+    impl Authored for RefMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+    impl Message for RefMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+    impl ConstructableMessage for RefMessage {
+        #[inline]
+        fn new(author_id: String, payload: String) -> Self { Self { author_id, payload } }
+    }
+
+    /// Minimal reference action that is just an actor, a basis and extra messages.
+    #[derive(Clone, Debug)]
+    struct RefAction {
+        actor_id: String,
+        basis:    RefMessage,
+        extra:    MessageSet<RefMessage>,
+    }
+    impl Actored for RefAction {
+        type ActorId = str;
+
+        #[inline]
+        fn actor_id(&self) -> &Self::ActorId { &self.actor_id }
+    }
+    impl Action for RefAction {
+        type Message = RefMessage;
+
+        #[inline]
+        fn basis(&self) -> &Self::Message { &self.basis }
+
+        #[inline]
+        fn extra(&self) -> &MessageSet<Self::Message> { &self.extra }
+
+        #[inline]
+        fn payload(&self) -> MessageSet<Self::Message> {
+            let mut payload: MessageSet<RefMessage> = self.extra.clone();
+            payload.add(self.basis.clone()).unwrap();
+            payload
+        }
+    }
+    impl ConstructableAction for RefAction {
+        #[inline]
+        fn new(actor_id: String, basis: Self::Message, extra: MessageSet<Self::Message>) -> Self { Self { actor_id, basis, extra } }
+    }
+
+
+    #[test]
+    fn test_check_message_roundtrip() { check_message_roundtrip::<RefMessage>("amy".into(), "foo.".into()); }
+
+    #[test]
+    fn test_check_message_roundtrip_boxed() { check_message_roundtrip::<Box<RefMessage>>("amy".into(), "foo.".into()); }
+
+    #[test]
+    fn test_check_action_roundtrip() {
+        let basis: RefMessage = RefMessage::new("amy".into(), "foo.".into());
+        let extra: MessageSet<RefMessage> =
+            MessageSet::from([RefMessage::new("bob".into(), "bar.".into()), RefMessage::new("cho".into(), "baz :- bar.".into())]);
+        check_action_roundtrip::<RefAction>("amy".into(), basis, extra);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_check_action_roundtrip_violated() {
+        /// Action that forgets its extra messages in its payload.
+        #[derive(Clone, Debug)]
+        struct Forgetful(RefAction);
+        impl Actored for Forgetful {
+            type ActorId = str;
+
+            #[inline]
+            fn actor_id(&self) -> &Self::ActorId { self.0.actor_id() }
+        }
+        impl Action for Forgetful {
+            type Message = RefMessage;
+
+            #[inline]
+            fn basis(&self) -> &Self::Message { self.0.basis() }
+
+            #[inline]
+            fn extra(&self) -> &MessageSet<Self::Message> { self.0.extra() }
+
+            #[inline]
+            fn payload(&self) -> MessageSet<Self::Message> { MessageSet::from([self.0.basis.clone()]) }
+        }
+        impl ConstructableAction for Forgetful {
+            #[inline]
+            fn new(actor_id: String, basis: Self::Message, extra: MessageSet<Self::Message>) -> Self {
+                Self(RefAction::new(actor_id, basis, extra))
+            }
+        }
+
+        let basis: RefMessage = RefMessage::new("amy".into(), "foo.".into());
+        let extra: MessageSet<RefMessage> = MessageSet::from([RefMessage::new("bob".into(), "bar.".into())]);
+        check_action_roundtrip::<Forgetful>("amy".into(), basis, extra);
+    }
+}
@@ -0,0 +1,201 @@
+//  GRAPH.rs
+//    by Lut99
+//
+//  Created:
+//    15 Oct 2026, 14:29:23
+//  Last edited:
+//    15 Oct 2026, 16:15:52
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines conversion of actions to [`petgraph`] graphs showing how they
+//!   depend on messages and agreements.
+//
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::actions::Action;
+use crate::auxillary::Identifiable;
+use crate::collections::set::{InfallibleSet, Set};
+use crate::messages::MessageSet;
+
+
+/***** AUXILLARY *****/
+/// The graph returned by [`dependency_graph()`].
+pub type DependencyGraph<'a, A> = DiGraph<NodeRef<'a, <A as Action>::Message, A>, ()>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines the nodes in a [`dependency_graph()`].
+///
+/// # Generics
+/// - `'a`: The lifetime of the set of actions from which the graph is built.
+/// - `M`: The type of messages used by the actions.
+/// - `A`: The type of actions.
+#[derive(Debug)]
+pub enum NodeRef<'a, M, A> {
+    /// A message that is used as the [basis](Action::basis()) of at least one action.
+    Agreement(&'a M),
+    /// A message that is only used as [extra](Action::extra()) justification by actions.
+    Message(&'a M),
+    /// An action.
+    Action(&'a A),
+}
+impl<'a, M, A> Clone for NodeRef<'a, M, A> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+impl<'a, M, A> Copy for NodeRef<'a, M, A> {}
+
+
+
+/// Builds a graph that shows how actions depend on messages and agreements.
+///
+/// Every action gets its own node, even if it is equal to another action. Messages, on the other
+/// hand, are identified by their [`Identifiable::id()`], such that every message occurs only once
+/// regardless of how many actions use it. A message is a [`NodeRef::Agreement`] if any action
+/// uses it as its [basis](Action::basis()), and a [`NodeRef::Message`] otherwise.
+///
+/// Edges point from every action to the messages it depends on, i.e., its basis and its
+/// [extra](Action::extra()) messages.
+///
+/// # Arguments
+/// - `actions`: The set of actions to build the graph of.
+///
+/// # Returns
+/// A [`DependencyGraph`] with a node per action and per unique message.
+///
+/// # Errors
+/// This function errors if the set of actions failed to be iterated.
+pub fn dependency_graph<'a, A, S>(actions: &'a S) -> Result<DependencyGraph<'a, A>, S::Error>
+where
+    A: 'a + Action,
+    A::Message: Eq + Hash + Identifiable,
+    S: Set<A>,
+{
+    let mut graph: DependencyGraph<'a, A> = DiGraph::new();
+    let mut msgs: HashMap<&'a <A::Message as Identifiable>::Id, NodeIndex> = HashMap::new();
+
+    // First, add the agreements, so they take precedence over the same message used as extra
+    for act in actions.iter()? {
+        let basis: &'a A::Message = act.basis();
+        msgs.entry(basis.id()).or_insert_with(|| graph.add_node(NodeRef::Agreement(basis)));
+    }
+
+    // Then add the actions and their dependencies
+    for act in actions.iter()? {
+        let node: NodeIndex = graph.add_node(NodeRef::Action(act));
+        graph.update_edge(node, msgs[act.basis().id()], ());
+        for msg in <MessageSet<A::Message> as InfallibleSet<A::Message>>::iter(act.extra()) {
+            let dep: NodeIndex = *msgs.entry(msg.id()).or_insert_with(|| graph.add_node(NodeRef::Message(msg)));
+            graph.update_edge(node, dep, ());
+        }
+    }
+    Ok(graph)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use petgraph::Direction;
+
+    use super::*;
+    use crate::auxillary::{Actored, Authored};
+    use crate::messages::Message;
+
+
+    /// Minimal message with an identifier.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct TestMessage {
+        id: &'static str,
+    }
+    impl Identifiable for TestMessage {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { self.id }
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { "amy" }
+    }
+    impl Message for TestMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { self.id }
+    }
+
+    /// Minimal action over [`TestMessage`]s.
+    #[derive(Debug, PartialEq)]
+    struct TestAction {
+        actor_id: &'static str,
+        basis:    TestMessage,
+        extra:    MessageSet<TestMessage>,
+    }
+    impl Actored for TestAction {
+        type ActorId = str;
+
+        #[inline]
+        fn actor_id(&self) -> &Self::ActorId { self.actor_id }
+    }
+    impl Action for TestAction {
+        type Message = TestMessage;
+
+        #[inline]
+        fn basis(&self) -> &Self::Message { &self.basis }
+
+        #[inline]
+        fn extra(&self) -> &MessageSet<Self::Message> { &self.extra }
+
+        #[inline]
+        fn payload(&self) -> MessageSet<Self::Message> { Set::iter(&self.extra).unwrap().cloned().chain([self.basis.clone()]).collect() }
+    }
+
+
+    #[test]
+    fn test_dependency_graph() {
+        let actions: Vec<TestAction> = vec![
+            TestAction { actor_id: "amy", basis: TestMessage { id: "agree" }, extra: MessageSet::from([TestMessage { id: "x" }]) },
+            TestAction { actor_id: "bob", basis: TestMessage { id: "agree" }, extra: MessageSet::from([TestMessage { id: "y" }]) },
+        ];
+        let graph: DependencyGraph<TestAction> = dependency_graph(&actions).unwrap();
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 4);
+
+        // The shared basis is one agreement, used by both actions
+        let agreements: Vec<NodeIndex> =
+            graph.node_indices().filter(|i| matches!(graph.node_weight(*i), Some(NodeRef::Agreement(TestMessage { id: "agree" })))).collect();
+        assert_eq!(agreements.len(), 1);
+        let mut users: Vec<&str> = graph
+            .neighbors_directed(agreements[0], Direction::Incoming)
+            .map(|i| match graph.node_weight(i) {
+                Some(NodeRef::Action(act)) => act.actor_id,
+                other => panic!("Expected an action to depend on the agreement, got {other:?}"),
+            })
+            .collect();
+        users.sort();
+        assert_eq!(users, vec!["amy", "bob"]);
+
+        // The extras are plain messages, used by one action each
+        for (id, actor) in [("x", "amy"), ("y", "bob")] {
+            let msg: NodeIndex = graph.node_indices().find(|i| matches!(graph.node_weight(*i), Some(NodeRef::Message(m)) if m.id == id)).unwrap();
+            let users: Vec<NodeIndex> = graph.neighbors_directed(msg, Direction::Incoming).collect();
+            assert_eq!(users.len(), 1);
+            assert!(matches!(graph.node_weight(users[0]), Some(NodeRef::Action(act)) if act.actor_id == actor));
+        }
+    }
+}
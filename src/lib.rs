@@ -4,7 +4,7 @@
 //  Created:
 //    13 Mar 2024, 15:36:47
 //  Last edited:
-//    13 Jan 2025, 12:03:18
+//    15 Oct 2026, 14:29:23
 //  Auto updated?
 //    Yes
 //
@@ -19,6 +19,9 @@ pub mod actions;
 pub mod actors;
 pub mod auxillary;
 pub mod collections;
+pub mod conformance;
+#[cfg(feature = "petgraph")]
+pub mod graph;
 pub mod messages;
 pub mod policies;
 pub mod runtime;
@@ -4,7 +4,7 @@
 //  Created:
 //    10 Dec 2024, 11:43:49
 //  Last edited:
-//    21 Jan 2025, 14:58:43
+//    15 Oct 2026, 16:12:37
 //  Auto updated?
 //    Yes
 //
@@ -12,14 +12,16 @@
 //!   Defines messages & message sets.
 //
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::fmt::{Debug, Formatter, Result as FResult};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::rc::Rc;
 use std::sync::Arc;
 
-use crate::auxillary::Authored;
+use auto_traits::pointer_impls;
+
+use crate::auxillary::{Authored, Identifiable};
 use crate::collections::set::{Set, SetSync};
 
 
@@ -89,6 +91,41 @@ message_ptr_impl!(Arc<T>);
 
 
 
+/// Defines a message that may be a reply to another message.
+///
+/// This allows conversational protocols to be expressed, where messages are organised in threads
+/// (see [`MessageSet::thread_of()`]).
+#[pointer_impls]
+pub trait ReplyMessage: Identifiable + Message {
+    /// Returns the message that this message is a reply to.
+    ///
+    /// # Returns
+    /// The identifier of the parent message, or [`None`] if this message isn't a reply. If it's
+    /// not implemented, it yields [`None`].
+    #[inline]
+    fn in_reply_to(&self) -> Option<&Self::Id> { None }
+}
+
+
+
+/// Defines an object (typically a [`Message`]) that is part of an ordered sequence.
+///
+/// This is used for, e.g., policy documents that are too large for one message and are
+/// therefore chunked across several (see
+/// [`ChunkedExtractor`](crate::policies::ChunkedExtractor)).
+#[pointer_impls]
+pub trait Sequenced {
+    /// Returns the position of this object in its sequence.
+    ///
+    /// Sequences are expected to start at `0` and be contiguous.
+    ///
+    /// # Returns
+    /// A [`u64`] encoding the sequence number.
+    fn seq(&self) -> u64;
+}
+
+
+
 /// Defines a constructor for a message.
 ///
 /// This is a more powerful version of a message that can also be constructed, but needn't be one itself.
@@ -156,6 +193,184 @@ where
 
 
 
+/// Defines a constructor for a message that is part of a sequence.
+///
+/// This is used by the [`MessageSequencer`] to construct successive messages of one author.
+pub trait ConstructableSequencedMessage: ConstructableMessage + Sequenced
+where
+    Self::AuthorId: ToOwned,
+    Self::Payload: ToOwned,
+{
+    /// Constructor for a new message with the given author, sequence number and payload.
+    ///
+    /// The sequence number must be folded into the message's [`Identifiable::id()`] (if it has
+    /// one) such that messages by the same author with different sequence numbers get different
+    /// identifiers. For example, `<author>:<seq>` would do. Furthermore, [`Sequenced::seq()`] must
+    /// return `seq`.
+    ///
+    /// # Arguments
+    /// - `author_id`: The identifier of the message's author.
+    /// - `seq`: The sequence number of the message.
+    /// - `payload`: The payload to add to the message.
+    ///
+    /// # Returns
+    /// A new Message.
+    fn new_sequenced(author_id: <Self::AuthorId as ToOwned>::Owned, seq: u64, payload: <Self::Payload as ToOwned>::Owned) -> Self
+    where
+        Self: Sized;
+}
+
+// Manual pointer impls (for some of them)
+impl<T> ConstructableSequencedMessage for Box<T>
+where
+    T: ConstructableSequencedMessage,
+    T::AuthorId: ToOwned,
+    T::Payload: ToOwned,
+{
+    #[inline]
+    fn new_sequenced(author_id: <Self::AuthorId as ToOwned>::Owned, seq: u64, payload: <Self::Payload as ToOwned>::Owned) -> Self
+    where
+        Self: Sized,
+    {
+        Box::new(<T as ConstructableSequencedMessage>::new_sequenced(author_id, seq, payload))
+    }
+}
+impl<T> ConstructableSequencedMessage for Rc<T>
+where
+    T: ConstructableSequencedMessage,
+    T::AuthorId: ToOwned,
+    T::Payload: ToOwned,
+{
+    #[inline]
+    fn new_sequenced(author_id: <Self::AuthorId as ToOwned>::Owned, seq: u64, payload: <Self::Payload as ToOwned>::Owned) -> Self
+    where
+        Self: Sized,
+    {
+        Rc::new(<T as ConstructableSequencedMessage>::new_sequenced(author_id, seq, payload))
+    }
+}
+impl<T> ConstructableSequencedMessage for Arc<T>
+where
+    T: ConstructableSequencedMessage,
+    T::AuthorId: ToOwned,
+    T::Payload: ToOwned,
+{
+    #[inline]
+    fn new_sequenced(author_id: <Self::AuthorId as ToOwned>::Owned, seq: u64, payload: <Self::Payload as ToOwned>::Owned) -> Self
+    where
+        Self: Sized,
+    {
+        Arc::new(<T as ConstructableSequencedMessage>::new_sequenced(author_id, seq, payload))
+    }
+}
+
+
+
+/// Constructs successive messages of a single author with increasing sequence numbers.
+///
+/// This is useful for agents that emit a burst of related messages, e.g., when chunking a large
+/// policy document (see [`ChunkedExtractor`](crate::policies::ChunkedExtractor)). The first
+/// message gets sequence number `0`, the next `1`, and so on. How the sequence number ends up in
+/// the message's identifier is up to the [`ConstructableSequencedMessage`] implementation.
+///
+/// Once sequence number [`u64::MAX`] has been handed out, the sequencer is exhausted and will not
+/// construct any more messages (instead of wrapping around and producing duplicate identifiers).
+///
+/// # Generics
+/// - `M`: The type of [`ConstructableSequencedMessage`] to construct.
+pub struct MessageSequencer<M>
+where
+    M: ConstructableSequencedMessage,
+    M::AuthorId: ToOwned,
+    M::Payload: ToOwned,
+{
+    /// The author of all messages.
+    author_id: <M::AuthorId as ToOwned>::Owned,
+    /// The next sequence number to hand out, or [`None`] if we're exhausted.
+    next:      Option<u64>,
+}
+
+// Constructors
+impl<M> MessageSequencer<M>
+where
+    M: ConstructableSequencedMessage,
+    M::AuthorId: ToOwned,
+    M::Payload: ToOwned,
+{
+    /// Constructor for the MessageSequencer that starts at sequence number `0`.
+    ///
+    /// # Arguments
+    /// - `author_id`: The identifier of the author of all constructed messages.
+    ///
+    /// # Returns
+    /// A new MessageSequencer.
+    #[inline]
+    pub const fn new(author_id: <M::AuthorId as ToOwned>::Owned) -> Self { Self { author_id, next: Some(0) } }
+}
+impl<M> Debug for MessageSequencer<M>
+where
+    M: ConstructableSequencedMessage,
+    M::AuthorId: ToOwned,
+    M::Payload: ToOwned,
+    <M::AuthorId as ToOwned>::Owned: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let Self { author_id, next } = self;
+        let mut fmt = f.debug_struct("MessageSequencer");
+        fmt.field("author_id", author_id);
+        fmt.field("next", next);
+        fmt.finish()
+    }
+}
+
+// Sequencer
+impl<M> MessageSequencer<M>
+where
+    M: ConstructableSequencedMessage,
+    M::AuthorId: ToOwned,
+    M::Payload: ToOwned,
+    <M::AuthorId as ToOwned>::Owned: Clone,
+{
+    /// Constructs the next message in the sequence.
+    ///
+    /// # Arguments
+    /// - `payload`: The payload of the new message.
+    ///
+    /// # Returns
+    /// A new message with the next sequence number, or [`None`] if the sequencer is exhausted.
+    #[inline]
+    pub fn next(&mut self, payload: <M::Payload as ToOwned>::Owned) -> Option<M> {
+        let seq: u64 = self.next?;
+        self.next = seq.checked_add(1);
+        Some(M::new_sequenced(self.author_id.clone(), seq, payload))
+    }
+
+    /// Returns the sequence number that the next message will get.
+    ///
+    /// # Returns
+    /// The next sequence number, or [`None`] if the sequencer is exhausted.
+    #[inline]
+    pub const fn peek_seq(&self) -> Option<u64> { self.next }
+}
+
+
+
+/// Defines a portable snapshot of a [`MessageSet`].
+///
+/// Unlike a clone of a set, which is a live set that can be mutated further, a snapshot is a
+/// plain value that can be stored (e.g., serialized to disk) and later be
+/// [restored](MessageSet::restore()). This is useful for agents that want to checkpoint their
+/// view.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Snapshot<M> {
+    /// The messages in the set at the time of the snapshot.
+    pub messages: Vec<M>,
+}
+
+
+
 /// Defines a bunch of messages.
 #[derive(Clone, Debug)]
 pub struct MessageSet<M> {
@@ -191,16 +406,111 @@ impl<M> MessageSet<M> {
     /// A new MessageSet, ready to store messages.
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self { Self { data: HashSet::with_capacity(capacity) } }
+
+    /// Constructor for the MessageSet that builds it from an iterator, while reporting
+    /// duplicates.
+    ///
+    /// Like [`MessageSet::from_iter()`], a message that is equal to one already in the set is not
+    /// added (the first one wins). However, instead of silently dropping them, this constructor
+    /// returns such duplicates such that callers can detect accidental collisions.
+    ///
+    /// # Arguments
+    /// - `iter`: Something yielding the messages to put in the set.
+    ///
+    /// # Returns
+    /// A tuple of the new MessageSet, and the list of messages that were dropped because an equal
+    /// message was yielded earlier (in the order they were yielded).
+    #[inline]
+    pub fn from_iter_checked<I: IntoIterator<Item = M>>(iter: I) -> (Self, Vec<M>)
+    where
+        M: Eq + Hash,
+    {
+        let iter = iter.into_iter();
+        let mut data: HashSet<M> = HashSet::with_capacity(iter.size_hint().0);
+        let mut dups: Vec<M> = Vec::new();
+        for msg in iter {
+            if data.contains(&msg) {
+                dups.push(msg);
+            } else {
+                data.insert(msg);
+            }
+        }
+        (Self { data }, dups)
+    }
+
+    /// Constructor for the MessageSet that builds it from an iterator, while reporting
+    /// identifier collisions.
+    ///
+    /// Unlike [`MessageSet::from_iter_checked()`], messages are compared by their
+    /// [`Identifiable::id()`] instead of by equality. Hence, this also catches messages that have
+    /// the same identifier but different contents, which would otherwise both end up in the set.
+    /// Of all messages with the same identifier, only the first one is added.
+    ///
+    /// # Arguments
+    /// - `iter`: Something yielding the messages to put in the set.
+    ///
+    /// # Returns
+    /// A tuple of the new MessageSet, and the list of messages that were dropped because a
+    /// message with the same identifier was yielded earlier (in the order they were yielded).
+    #[inline]
+    pub fn from_iter_checked_by_id<I: IntoIterator<Item = M>>(iter: I) -> (Self, Vec<M>)
+    where
+        M: Eq + Hash + Identifiable,
+    {
+        let msgs: Vec<M> = iter.into_iter().collect();
+
+        // Find which messages are the first with their ID
+        let mut ids: HashSet<&M::Id> = HashSet::with_capacity(msgs.len());
+        let firsts: Vec<bool> = <[M]>::iter(&msgs).map(|msg| ids.insert(msg.id())).collect();
+
+        // Then split them accordingly
+        let mut data: HashSet<M> = HashSet::with_capacity(msgs.len());
+        let mut dups: Vec<M> = Vec::new();
+        for (msg, first) in msgs.into_iter().zip(firsts) {
+            if first {
+                data.insert(msg);
+            } else {
+                dups.push(msg);
+            }
+        }
+        (Self { data }, dups)
+    }
 }
 
-// Ops
-impl<M: Eq> Eq for MessageSet<M> {}
-impl<M: Hash> Hash for MessageSet<M> {
+// MessageSet
+impl<M> MessageSet<M> {
+    /// Estimates the memory footprint of this set, in bytes.
+    ///
+    /// This accounts for the set itself and for the capacity of its backing storage (i.e., room
+    /// for [`size_of::<M>()`](std::mem::size_of()) per element, plus one control byte). Note that
+    /// it is only an estimate: it excludes any heap memory that the elements themselves point to
+    /// (e.g., the contents of a [`String`] or [`Arc`] payload), and it ignores any allocator
+    /// overhead.
+    ///
+    /// # Returns
+    /// The estimated number of bytes used by this set.
     #[inline]
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        /* We use the sort-by-hash-trick */
-        // First, get a **deterministic** ordering on the items. This is important, as the
-        // conceptually same set must always give the same ordering!
+    pub fn estimated_bytes(&self) -> usize { std::mem::size_of::<Self>() + self.data.capacity() * (std::mem::size_of::<M>() + 1) }
+
+    /// Returns the messages in this set in a deterministic order.
+    ///
+    /// This is the same ordering that is used to compute the set's [`Hash`]. Hence, two sets with
+    /// the same contents always yield the same ordering, regardless of the order in which the
+    /// messages were inserted. This is useful for reproducible, diffable dumps of a set.
+    ///
+    /// Note that the ordering is derived from the messages' hashes. As such, it is deterministic
+    /// across sets and runs, but otherwise meaningless. Messages with colliding hashes are
+    /// ordered arbitrarily among themselves.
+    ///
+    /// # Returns
+    /// A list of references to the messages in this set, in canonical order.
+    #[inline]
+    pub fn canonical_order(&self) -> Vec<&M>
+    where
+        M: Hash,
+    {
+        // We get a **deterministic** ordering on the items. This is important, as the conceptually
+        // same set must always give the same ordering!
         let mut elems: Vec<&M> = self.data.iter().collect();
         elems.sort_by_key(|m| {
             // Now comes the real trick: we simply hash the element first and then sort the hashes.
@@ -211,9 +521,102 @@ impl<M: Hash> Hash for MessageSet<M> {
             m.hash(&mut hasher);
             hasher.finish()
         });
+        elems
+    }
+
+    /// Takes a snapshot of this set.
+    ///
+    /// # Returns
+    /// A [`Snapshot`] containing copies of all the messages in this set.
+    #[inline]
+    pub fn checkpoint(&self) -> Snapshot<M>
+    where
+        M: Clone,
+    {
+        Snapshot { messages: self.data.iter().cloned().collect() }
+    }
+
+    /// Restores this set to the state of an earlier snapshot.
+    ///
+    /// Any messages currently in the set are discarded.
+    ///
+    /// # Arguments
+    /// - `snapshot`: The [`Snapshot`] to restore.
+    #[inline]
+    pub fn restore(&mut self, snapshot: Snapshot<M>)
+    where
+        M: Eq + Hash,
+    {
+        self.data = snapshot.messages.into_iter().collect();
+    }
+
+    /// Splits this set into sub-sets by some key.
+    ///
+    /// The sub-sets borrow the messages in this set. They are still [`MessageSet`]s, so equal
+    /// messages are still collapsed into one (as in this set).
+    ///
+    /// # Arguments
+    /// - `key`: A closure that maps every message to the key of the sub-set it belongs to (e.g.,
+    ///   its author).
+    ///
+    /// # Returns
+    /// A map from keys to the sub-set of messages with that key. Keys to which no message maps do
+    /// not occur.
+    #[inline]
+    pub fn partition_by<K, F>(&self, mut key: F) -> HashMap<K, MessageSet<&M>>
+    where
+        M: Eq + Hash,
+        K: Eq + Hash,
+        F: FnMut(&M) -> K,
+    {
+        let mut parts: HashMap<K, MessageSet<&M>> = HashMap::new();
+        for msg in &self.data {
+            parts.entry(key(msg)).or_default().data.insert(msg);
+        }
+        parts
+    }
 
-        // Using this ordering, we can hash the array to get a predictable hash regardless of set
-        // order.
+    /// Reconstructs the conversation that started at a particular message.
+    ///
+    /// This walks the set following [`ReplyMessage::in_reply_to()`]-links, starting at `root`.
+    ///
+    /// # Arguments
+    /// - `root`: The identifier of the message that started the conversation.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the messages in the thread. The root is yielded first, followed by all
+    /// (transitive) replies to it in breadth-first order. The order of replies at the same depth
+    /// is unspecified. If there is no message with ID `root`, nothing is yielded.
+    #[inline]
+    pub fn thread_of(&self, root: &<M as Identifiable>::Id) -> impl '_ + Iterator<Item = &M>
+    where
+        M: ReplyMessage,
+    {
+        let mut thread: Vec<&M> = self.data.iter().filter(|msg| msg.id() == root).collect();
+        let mut seen: HashSet<&M> = <[&M]>::iter(&thread).copied().collect();
+        let mut i: usize = 0;
+        while i < thread.len() {
+            let parent: &M::Id = thread[i].id();
+            for msg in &self.data {
+                if msg.in_reply_to() == Some(parent) && seen.insert(msg) {
+                    thread.push(msg);
+                }
+            }
+            i += 1;
+        }
+        thread.into_iter()
+    }
+}
+
+// Ops
+impl<M: Eq> Eq for MessageSet<M> {}
+impl<M: Hash> Hash for MessageSet<M> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        /* We use the sort-by-hash-trick */
+        // Using the canonical ordering, we can hash the array to get a predictable hash regardless
+        // of set order.
+        let elems: Vec<&M> = self.canonical_order();
         elems.hash(state)
     }
 }
@@ -328,6 +731,55 @@ impl<M: serde::Serialize> serde::Serialize for MessageSet<M> {
     }
 }
 
+// JSON
+#[cfg(feature = "json")]
+impl<M> MessageSet<M> {
+    /// Dumps this set to a JSON array.
+    ///
+    /// Every element of the array is the JSON representation of one message, as given by its
+    /// [`Serialize`](serde::Serialize)-implementation. The messages are dumped in
+    /// [canonical order](MessageSet::canonical_order()), such that sets with the same contents
+    /// always produce the same array.
+    ///
+    /// # Returns
+    /// A [`serde_json::Value::Array`] with the messages in this set.
+    ///
+    /// # Errors
+    /// This function errors if any of the messages failed to serialize to JSON (e.g., because it
+    /// contains a map with non-string keys).
+    #[inline]
+    pub fn to_json_array(&self) -> Result<serde_json::Value, serde_json::Error>
+    where
+        M: Hash + serde::Serialize,
+    {
+        let elems: Vec<serde_json::Value> = self.canonical_order().into_iter().map(serde_json::to_value).collect::<Result<_, _>>()?;
+        Ok(serde_json::Value::Array(elems))
+    }
+
+    /// Parses a set from a JSON array.
+    ///
+    /// This is the inverse of [`MessageSet::to_json_array()`]. The order of the array's elements
+    /// does not matter, and duplicate messages are collapsed into one.
+    ///
+    /// # Arguments
+    /// - `value`: The [`serde_json::Value`] to parse.
+    ///
+    /// # Returns
+    /// A new MessageSet with the messages in `value`.
+    ///
+    /// # Errors
+    /// This function errors if `value` is not an array, or if any of its elements failed to
+    /// deserialize as an `M`.
+    #[inline]
+    pub fn from_json_array(value: serde_json::Value) -> Result<Self, serde_json::Error>
+    where
+        M: Eq + Hash + serde::de::DeserializeOwned,
+    {
+        let elems: Vec<M> = serde_json::from_value(value)?;
+        Ok(Self::from(elems))
+    }
+}
+
 // From
 impl<I: IntoIterator<Item = M>, M: Eq + Hash> From<I> for MessageSet<M> {
     #[inline]
@@ -337,3 +789,254 @@ impl<M: Eq + Hash> FromIterator<M> for MessageSet<M> {
     #[inline]
     fn from_iter<T: IntoIterator<Item = M>>(iter: T) -> Self { Self::from(iter) }
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Minimal message with an explicit identifier.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    struct TestMessage {
+        id:        String,
+        author_id: String,
+        seq:       u64,
+        payload:   String,
+    }
+    impl Identifiable for TestMessage {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Authored for TestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+    impl Message for TestMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+    impl Sequenced for TestMessage {
+        #[inline]
+        fn seq(&self) -> u64 { self.seq }
+    }
+    impl ConstructableMessage for TestMessage {
+        #[inline]
+        fn new(author_id: String, payload: String) -> Self { Self::new_sequenced(author_id, 0, payload) }
+    }
+    impl ConstructableSequencedMessage for TestMessage {
+        #[inline]
+        fn new_sequenced(author_id: String, seq: u64, payload: String) -> Self { Self { id: format!("{author_id}:{seq}"), author_id, seq, payload } }
+    }
+
+    /// Shorthand for creating a [`TestMessage`].
+    fn msg(id: &str, author_id: &str, payload: &str) -> TestMessage {
+        TestMessage { id: id.into(), author_id: author_id.into(), seq: 0, payload: payload.into() }
+    }
+
+
+    /// Minimal message that may reply to another.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct ReplyTestMessage {
+        id:     String,
+        parent: Option<String>,
+    }
+    impl Identifiable for ReplyTestMessage {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl Authored for ReplyTestMessage {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { "amy" }
+    }
+    impl Message for ReplyTestMessage {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.id }
+    }
+    impl ReplyMessage for ReplyTestMessage {
+        #[inline]
+        fn in_reply_to(&self) -> Option<&Self::Id> { self.parent.as_deref() }
+    }
+
+    /// Shorthand for creating a [`ReplyTestMessage`].
+    fn reply(id: &str, parent: Option<&str>) -> ReplyTestMessage { ReplyTestMessage { id: id.into(), parent: parent.map(Into::into) } }
+
+    #[test]
+    fn test_message_set_from_iter_checked() {
+        let (set, dups): (MessageSet<TestMessage>, Vec<TestMessage>) =
+            MessageSet::from_iter_checked([msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("a", "amy", "foo."), msg("a", "amy", "baz.")]);
+        assert_eq!(set, MessageSet::from([msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("a", "amy", "baz.")]));
+        assert_eq!(dups, vec![msg("a", "amy", "foo.")]);
+    }
+
+    #[test]
+    fn test_message_set_from_iter_checked_by_id() {
+        let (set, dups): (MessageSet<TestMessage>, Vec<TestMessage>) =
+            MessageSet::from_iter_checked_by_id([msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("a", "cho", "baz.")]);
+        assert_eq!(set, MessageSet::from([msg("a", "amy", "foo."), msg("b", "bob", "bar.")]));
+        assert_eq!(dups, vec![msg("a", "cho", "baz.")]);
+
+        // Exact copies collide too
+        let (set, dups): (MessageSet<TestMessage>, Vec<TestMessage>) =
+            MessageSet::from_iter_checked_by_id([msg("a", "amy", "foo."), msg("a", "amy", "foo.")]);
+        assert_eq!(set, MessageSet::from([msg("a", "amy", "foo.")]));
+        assert_eq!(dups, vec![msg("a", "amy", "foo.")]);
+    }
+
+    #[test]
+    fn test_message_set_estimated_bytes() {
+        let empty: MessageSet<TestMessage> = MessageSet::new();
+        let mut set: MessageSet<TestMessage> = MessageSet::from([msg("a", "amy", "foo.")]);
+        let before: usize = set.estimated_bytes();
+        assert!(before > 0);
+        assert!(before > empty.estimated_bytes());
+
+        // Growing the set grows the estimate
+        for i in 0..64 {
+            set.add(msg(&format!("b{i}"), "bob", "bar.")).unwrap();
+        }
+        assert!(set.estimated_bytes() > before);
+    }
+
+    #[test]
+    fn test_message_set_canonical_order() {
+        let msgs: [TestMessage; 4] = [msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("c", "cho", "baz."), msg("d", "dan", "quux.")];
+        let fwd: MessageSet<TestMessage> = msgs.iter().cloned().collect();
+        let rev: MessageSet<TestMessage> = msgs.iter().rev().cloned().collect();
+        let mut mixed: MessageSet<TestMessage> = MessageSet::with_capacity(64);
+        for i in [2, 0, 3, 1] {
+            mixed.add(msgs[i].clone()).unwrap();
+        }
+
+        // Regardless of insertion order (or capacity), the order is the same
+        let order: Vec<&TestMessage> = fwd.canonical_order();
+        assert_eq!(order.len(), 4);
+        assert_eq!(rev.canonical_order(), order);
+        assert_eq!(mixed.canonical_order(), order);
+    }
+
+    #[test]
+    fn test_message_set_checkpoint_restore() {
+        let mut set: MessageSet<TestMessage> = MessageSet::from([msg("a", "amy", "foo."), msg("b", "bob", "bar.")]);
+        let orig: MessageSet<TestMessage> = set.clone();
+        let snapshot: Snapshot<TestMessage> = set.checkpoint();
+
+        // Mutate the set...
+        set.add(msg("c", "cho", "baz.")).unwrap();
+        set.clear().unwrap();
+        set.add(msg("d", "dan", "quux.")).unwrap();
+        assert_ne!(set, orig);
+
+        // ...and restore it
+        set.restore(snapshot);
+        assert_eq!(set, orig);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_message_set_json_array() {
+        let set: MessageSet<TestMessage> = MessageSet::from([msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("c", "cho", "baz.")]);
+        let value: serde_json::Value = set.to_json_array().unwrap();
+        assert_eq!(value.as_array().map(Vec::len), Some(3));
+        assert_eq!(MessageSet::<TestMessage>::from_json_array(value).unwrap(), set);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_message_set_json_array_errors() {
+        // Not an array
+        let value: serde_json::Value = serde_json::json!({ "id": "a", "author_id": "amy", "seq": 0, "payload": "foo." });
+        assert!(MessageSet::<TestMessage>::from_json_array(value).is_err());
+
+        // One malformed element
+        let value: serde_json::Value =
+            serde_json::json!([{ "id": "a", "author_id": "amy", "seq": 0, "payload": "foo." }, { "id": "b", "author_id": "bob", "seq": "zero" }]);
+        assert!(MessageSet::<TestMessage>::from_json_array(value).is_err());
+    }
+
+    #[test]
+    fn test_message_set_partition_by() {
+        let set: MessageSet<TestMessage> = MessageSet::from([msg("a", "amy", "foo."), msg("b", "bob", "bar."), msg("c", "amy", "baz.")]);
+        let parts: HashMap<String, MessageSet<&TestMessage>> = set.partition_by(|m| m.author_id.clone());
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts["amy"], MessageSet::from([&msg("a", "amy", "foo."), &msg("c", "amy", "baz.")]));
+        assert_eq!(parts["bob"], MessageSet::from([&msg("b", "bob", "bar.")]));
+        assert!(!parts.contains_key("cho"));
+    }
+
+    #[test]
+    fn test_message_set_thread_of() {
+        let set: MessageSet<ReplyTestMessage> = MessageSet::from([
+            reply("c", Some("b")),
+            reply("x", None),
+            reply("b", Some("a")),
+            reply("y", Some("x")),
+            reply("a", None),
+        ]);
+        let thread: Vec<&str> = set.thread_of("a").map(|m| m.id.as_str()).collect();
+        assert_eq!(thread, vec!["a", "b", "c"]);
+        let thread: Vec<&str> = set.thread_of("b").map(|m| m.id.as_str()).collect();
+        assert_eq!(thread, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_message_set_thread_of_unknown() {
+        let set: MessageSet<ReplyTestMessage> = MessageSet::from([reply("a", None), reply("b", Some("a"))]);
+        assert_eq!(set.thread_of("z").count(), 0);
+
+        // Replies to a missing root aren't found either
+        let set: MessageSet<ReplyTestMessage> = MessageSet::from([reply("b", Some("a"))]);
+        assert_eq!(set.thread_of("a").count(), 0);
+    }
+
+    #[test]
+    fn test_message_set_thread_of_cycle() {
+        let set: MessageSet<ReplyTestMessage> = MessageSet::from([reply("a", Some("c")), reply("b", Some("a")), reply("c", Some("b"))]);
+        let thread: Vec<&str> = set.thread_of("a").map(|m| m.id.as_str()).collect();
+        assert_eq!(thread, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_message_sequencer() {
+        let mut seqr: MessageSequencer<TestMessage> = MessageSequencer::new("amy".into());
+        assert_eq!(seqr.peek_seq(), Some(0));
+        let msgs: Vec<TestMessage> = ["a.", "b.", "c.", "d.", "e."].into_iter().map(|p| seqr.next(p.into()).unwrap()).collect();
+        assert_eq!(seqr.peek_seq(), Some(5));
+
+        // Sequence numbers and identifiers are ordered and unique
+        assert_eq!(<[TestMessage]>::iter(&msgs).map(Sequenced::seq).collect::<Vec<u64>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(<[TestMessage]>::iter(&msgs).map(|m| m.id.as_str()).collect::<Vec<&str>>(), vec!["amy:0", "amy:1", "amy:2", "amy:3", "amy:4"]);
+        assert_eq!(<[TestMessage]>::iter(&msgs).map(Identifiable::id).collect::<HashSet<&str>>().len(), 5);
+        assert!(<[TestMessage]>::iter(&msgs).all(|m| m.author_id == "amy"));
+        assert_eq!(<[TestMessage]>::iter(&msgs).map(|m| m.payload.as_str()).collect::<Vec<&str>>(), vec!["a.", "b.", "c.", "d.", "e."]);
+    }
+
+    #[test]
+    fn test_message_sequencer_exhausted() {
+        let mut seqr: MessageSequencer<TestMessage> = MessageSequencer::new("amy".into());
+        seqr.next = Some(u64::MAX - 1);
+
+        assert_eq!(seqr.next("a.".into()).map(|m| m.seq), Some(u64::MAX - 1));
+        assert_eq!(seqr.next("b.".into()).map(|m| m.seq), Some(u64::MAX));
+        assert_eq!(seqr.peek_seq(), None);
+        assert_eq!(seqr.next("c.".into()), None);
+        assert_eq!(seqr.next("d.".into()), None);
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    10 Dec 2024, 12:00:42
 //  Last edited:
-//    23 Jan 2025, 16:55:22
+//    15 Oct 2026, 15:21:00
 //  Auto updated?
 //    Yes
 //
@@ -12,14 +12,112 @@
 //!   Defines the interface with policy in JustAct.
 //
 
+use std::borrow::Borrow;
 use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::hash::Hash;
 
 use auto_traits::pointer_impls;
 
 use crate::auxillary::{Affectored, Identifiable};
+use crate::collections::Singleton;
 use crate::collections::map::InfallibleMap;
 use crate::collections::set::{InfallibleSet, Set};
-use crate::messages::Message;
+use crate::messages::{Message, Sequenced};
+
+
+/***** ERRORS *****/
+/// Defines the errors originating from [`ChunkedExtractor::extract_chunked()`].
+#[derive(Debug)]
+pub enum ChunkError<ES, EE> {
+    /// Two chunks had the same sequence number.
+    Duplicate { seq: u64 },
+    /// A chunk was missing from the sequence.
+    Gap { missing: u64 },
+    /// Not all chunks were written by the same author.
+    MixedAuthors,
+    /// Failed to iterate over the set of chunks.
+    Set(ES),
+    /// Failed to extract the policy from the concatenated chunks.
+    Extract(EE),
+}
+impl<ES: Display, EE: Display> Display for ChunkError<ES, EE> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::Duplicate { seq } => write!(f, "Found multiple chunks with sequence number {seq}"),
+            Self::Gap { missing } => write!(f, "Missing chunk with sequence number {missing}"),
+            Self::MixedAuthors => write!(f, "Not all chunks are written by the same author"),
+            Self::Set(err) => err.fmt(f),
+            Self::Extract(err) => err.fmt(f),
+        }
+    }
+}
+impl<ES: Error, EE: Error> Error for ChunkError<ES, EE> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn 'static + Error)> {
+        match self {
+            Self::Duplicate { .. } => None,
+            Self::Gap { .. } => None,
+            Self::MixedAuthors => None,
+            Self::Set(err) => err.source(),
+            Self::Extract(err) => err.source(),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Explains why a fact has the truth value it has in a [`Denotation`].
+///
+/// See [`Denotation::explain()`] for more information.
+///
+/// # Generics
+/// - `F`: The type of facts in the denotation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TruthExplanation<F> {
+    /// The denotation cannot explain its truths.
+    Opaque,
+    /// The fact is true because it's given unconditionally.
+    Given,
+    /// The fact is true because it's derived from other facts (which are all true).
+    Derived { antecedents: Vec<F> },
+    /// The fact is false because there is no way to derive it.
+    Underivable,
+    /// The fact is unknowable because its derivation depends on facts that contradict each other.
+    Unknowable { conflicting: Vec<F> },
+}
+
+/// The result of a lenient extraction: the policy composed from all parseable messages, together
+/// with the messages that failed to parse (and why).
+///
+/// See [`Extractor::extract_lenient()`] for more information.
+pub type LenientPolicy<'m, M, P, E> = (P, Vec<(&'m M, E)>);
+
+/// Defines payloads that can be concatenated, e.g., to reassemble chunked policy.
+///
+/// See [`ChunkedExtractor::extract_chunked()`] for more information.
+pub trait Concat: ToOwned {
+    /// Appends this payload to the end of an owned one.
+    ///
+    /// # Arguments
+    /// - `buf`: The owned payload to append to.
+    fn append_to(&self, buf: &mut Self::Owned);
+}
+impl Concat for str {
+    #[inline]
+    fn append_to(&self, buf: &mut String) { buf.push_str(self); }
+}
+impl<T: Clone> Concat for [T] {
+    #[inline]
+    fn append_to(&self, buf: &mut Vec<T>) { buf.extend_from_slice(self); }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -65,6 +163,23 @@ pub trait Denotation: InfallibleMap<Self::Effect> + InfallibleSet<Self::Fact> {
     /// _unknowable_ (not just unknown). An example of this is a logical contradiction.
     fn truth_of(&self, fact: &Self::Fact) -> Option<bool>;
 
+    /// Explains why a given fact has the truth value reported by [`Denotation::truth_of()`].
+    ///
+    /// For derived facts, this yields the antecedents used in (one of) its derivations. These can
+    /// in turn be explained to obtain a full derivation trace.
+    ///
+    /// # Arguments
+    /// - `fact`: Some [`Denotation::Fact`] of which we want to learn why it's (not) true.
+    ///
+    /// # Returns
+    /// A [`TruthExplanation`] describing why `fact` has its truth value. If it's not implemented,
+    /// it yields [`TruthExplanation::Opaque`].
+    #[inline]
+    fn explain(&self, fact: &Self::Fact) -> TruthExplanation<Self::Fact> {
+        let _ = fact;
+        TruthExplanation::Opaque
+    }
+
 
     /// Returns an iterator over the truths in this [`Denotation`].
     ///
@@ -83,6 +198,116 @@ pub trait Denotation: InfallibleMap<Self::Effect> + InfallibleSet<Self::Fact> {
     /// An [`Iterator`] over [`Denotatation::Effect`]s.
     #[inline]
     fn iter_effects<'s>(&'s self) -> impl 's + Iterator<Item = &'s Self::Effect> { <Self as InfallibleMap<Self::Effect>>::iter(self) }
+
+
+
+    /// Returns the truths that hold in both this and another [`Denotation`].
+    ///
+    /// A fact is considered to hold if [`Denotation::truth_of()`] returns [`Some(true)`]. Hence,
+    /// facts that are unknowable (i.e., [`None`]) in either denotation are **not** yielded.
+    ///
+    /// # Arguments
+    /// - `other`: Some other denotation to intersect with.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over [`Denotation::Fact`]s that are true in both denotations.
+    #[inline]
+    fn intersect_truths<'s>(&'s self, other: &'s Self) -> impl 's + Iterator<Item = &'s Self::Fact> {
+        self.iter_truths().filter(move |fact| other.truth_of(fact) == Some(true))
+    }
+
+    /// Returns the truths that hold in either this or another [`Denotation`].
+    ///
+    /// Every fact is yielded only once. Facts that are true in one denotation but unknowable
+    /// (i.e., [`None`]) in the other are still yielded, as they hold in at least one of them.
+    ///
+    /// # Arguments
+    /// - `other`: Some other denotation to unite with.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over [`Denotation::Fact`]s that are true in at least one of the
+    /// denotations. First yields the truths in `self`, then those only in `other`.
+    #[inline]
+    fn union_truths<'s>(&'s self, other: &'s Self) -> impl 's + Iterator<Item = &'s Self::Fact> {
+        self.iter_truths().chain(other.iter_truths().filter(move |fact| self.truth_of(fact) != Some(true)))
+    }
+
+    /// Returns the truths that hold in this [`Denotation`] but not in another.
+    ///
+    /// A fact does not hold in `other` if [`Denotation::truth_of()`] returns anything but
+    /// [`Some(true)`]. Hence, facts that are true here but unknowable (i.e., [`None`]) in `other`
+    /// are yielded.
+    ///
+    /// # Arguments
+    /// - `other`: Some other denotation of which to subtract the truths.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over [`Denotation::Fact`]s that are true in `self` but not in `other`.
+    #[inline]
+    fn difference_truths<'s>(&'s self, other: &'s Self) -> impl 's + Iterator<Item = &'s Self::Fact> {
+        self.iter_truths().filter(move |fact| other.truth_of(fact) != Some(true))
+    }
+}
+
+/// Extends a [`Denotation`] with knowledge about from which messages its truths were derived.
+///
+/// This is useful for debugging questions such as "which of my messages caused this fact?".
+pub trait ProvenanceDenotation: Denotation {
+    /// The type of identifiers of the messages from which truths are derived.
+    type MessageId: ?Sized + Eq + Hash;
+
+
+    /// Restricts this denotation to only those truths and effects that are derivable from a
+    /// subset of the source messages.
+    ///
+    /// A fact (or effect) may have mixed provenance, i.e., it may be derivable in multiple ways or
+    /// its derivation may depend on multiple messages. It is kept if and only if **at least one**
+    /// of its derivations relies **exclusively** on messages in `msgs`. Derivations that need any
+    /// message outside of `msgs` do not count.
+    ///
+    /// # Arguments
+    /// - `msgs`: An iterator over the identifiers of the messages to restrict to.
+    ///
+    /// # Returns
+    /// A new denotation that only contains the facts and effects derivable from `msgs`.
+    fn restrict_to<'a>(&self, msgs: impl Iterator<Item = &'a Self::MessageId>) -> Self
+    where
+        Self: Sized,
+        Self::MessageId: 'a;
+}
+
+/// Extends a [`Denotation`] such that it can be updated in-place when single facts change.
+///
+/// Recomputing a full denotation (via [`Policy::truths()`]) after every change is expensive for
+/// large policies. Denotations of policy languages whose derivation supports it can implement this
+/// trait to update their derived truths (and effects) incrementally instead.
+///
+/// # Correctness
+/// Suppose this denotation was computed from some policy `P` (i.e., `P.truths()`). Then:
+/// - after [`IncrementalDenotation::add_fact()`] with some fact `f`, the denotation must be
+///   equivalent to the denotation of `P` extended with `f` as an unconditional fact; and
+/// - after [`IncrementalDenotation::remove_fact()`] with some fact `f`, the denotation must be
+///   equivalent to the denotation of `P` without `f` as an unconditional fact. Facts that are
+///   still derivable through other means remain true.
+///
+/// This extends inductively to sequences of changes. "Equivalent" has the same meaning as in
+/// [`Policy::equivalent()`]: [`Denotation::truth_of()`] returns the same for all facts, and the
+/// effects are the same.
+pub trait IncrementalDenotation: Denotation {
+    /// Adds a new fact to the denotation, and updates any truths derived from it.
+    ///
+    /// # Arguments
+    /// - `fact`: The [`Denotation::Fact`] to assert.
+    fn add_fact(&mut self, fact: Self::Fact);
+
+    /// Removes a fact from the denotation, and updates any truths derived from it.
+    ///
+    /// Note that this only retracts the fact as an unconditional one. If it is still derivable
+    /// through other means, it remains true.
+    ///
+    /// # Arguments
+    /// - `fact`: The [`Denotation::Fact`] to retract.
+    fn remove_fact(&mut self, fact: &Self::Fact);
 }
 
 
@@ -107,6 +332,44 @@ pub trait Policy: Default {
     /// A [`Policy::Denotation`] that describes the active truths in the policy.
     fn truths(&self) -> Self::Denotation;
 
+    /// Checks whether this policy is semantically equivalent to another.
+    ///
+    /// Two policies are equivalent if they have the same denotation, regardless of how they are
+    /// written down. Concretely, this means that:
+    /// - for every fact that is true in either denotation, [`Denotation::truth_of()`] returns the
+    ///   same value in both; and
+    /// - both denotations carry exactly the same effects (compared by [`PartialEq`]).
+    ///
+    /// Unknowable facts (i.e., [`None`]) are compared like any other value. Hence, a fact that is
+    /// true in one denotation but unknowable in the other makes the policies inequivalent.
+    /// However, because denotations only iterate over their truths, a fact that is unknowable in
+    /// one denotation and false in the other cannot be discovered and will not be compared.
+    ///
+    /// # Arguments
+    /// - `other`: Some other policy to compare with.
+    ///
+    /// # Returns
+    /// True if both policies denote the same truths and effects, false otherwise.
+    fn equivalent(&self, other: &Self) -> bool
+    where
+        <Self::Denotation as Denotation>::Effect: PartialEq,
+    {
+        let lhs: Self::Denotation = self.truths();
+        let rhs: Self::Denotation = other.truths();
+
+        // Compare the truths, both ways
+        if lhs.iter_truths().chain(rhs.iter_truths()).any(|fact| lhs.truth_of(fact) != rhs.truth_of(fact)) {
+            return false;
+        }
+
+        // Compare the effects
+        if <Self::Denotation as InfallibleMap<_>>::len(&lhs) != <Self::Denotation as InfallibleMap<_>>::len(&rhs) {
+            return false;
+        }
+        let res: bool = lhs.iter_effects().all(|effect| <Self::Denotation as InfallibleMap<_>>::get(&rhs, effect.id()) == Some(effect));
+        res
+    }
+
 
 
     /// Composes a grander set of policy from this policy.
@@ -123,6 +386,25 @@ pub trait Policy: Default {
     /// # Arguments
     /// - `other`: Some other policy snippet to compose with.
     fn compose_mut(&mut self, other: Self);
+
+    /// Checks whether composing another snippet into this policy keeps it valid.
+    ///
+    /// This is a dry-run of [`Policy::compose()`], which leaves `self` untouched. It is useful
+    /// as a check before adopting a snippet, e.g., during negotiation.
+    ///
+    /// # Arguments
+    /// - `other`: Some other policy snippet to (hypothetically) compose with.
+    ///
+    /// # Returns
+    /// True if the composition of `self` and `other` [is valid](Policy::is_valid()), false
+    /// otherwise.
+    #[inline]
+    fn would_remain_valid(&self, other: &Self) -> bool
+    where
+        Self: Clone,
+    {
+        self.compose(other.clone()).is_valid()
+    }
 }
 
 /// Defines that something can extract policy.
@@ -154,4 +436,960 @@ where
     /// This function should error if and only if the policy contained in this object fails to
     /// parse.
     fn extract<'m, M: Message<AuthorId = A, Payload = C>>(&self, msgs: &'m impl Set<M>) -> Result<Self::Policy<'m>, Self::Error<'m>>;
+
+    /// Extracts the policy from something iterating over messages on a best-effort basis.
+    ///
+    /// Unlike [`Extractor::extract()`], this does not fail if some message is unparseable.
+    /// Instead, every message is extracted individually, and only the ones that succeeded are
+    /// [composed](Policy::compose_mut()) into the resulting policy. The ones that failed are
+    /// reported separately. This allows agents to proceed if a peer sends a garbage message.
+    ///
+    /// Note that this function only supports extractors that produce policy which does not borrow
+    /// from the extracted messages, as every message is extracted from a temporary set.
+    ///
+    /// # Arguments
+    /// - `msgs`: A [`Set`] of messages that we will be extracting from.
+    ///
+    /// # Returns
+    /// A tuple of the policy composed from all parseable messages, and a list of the messages
+    /// that failed to parse (together with the reason why).
+    ///
+    /// # Errors
+    /// This function errors if we failed to iterate over `msgs`.
+    fn extract_lenient<'m, M, S, P, E>(&self, msgs: &'m S) -> Result<LenientPolicy<'m, M, P, E>, S::Error>
+    where
+        M: Message<AuthorId = A, Payload = C>,
+        S: Set<M>,
+        for<'x> Self: Extractor<A, C, Policy<'x> = P, Error<'x> = E>,
+        P: Policy,
+    {
+        let mut policy: P = P::default();
+        let mut errs: Vec<(&'m M, E)> = Vec::new();
+        for msg in msgs.iter()? {
+            match <Self as Extractor<A, C>>::extract(self, &Singleton(msg)) {
+                Ok(snippet) => policy.compose_mut(snippet),
+                Err(err) => errs.push((msg, err)),
+            }
+        }
+        Ok((policy, errs))
+    }
+}
+
+/// Defines an extractor for policy languages that can span multiple messages.
+///
+/// Some policy documents are too large to fit in a single message, and are instead chunked across
+/// several of them. These chunks cannot be parsed individually and then composed; instead, their
+/// payloads must first be concatenated (in order) and then parsed as a whole.
+///
+/// # Generics
+/// - `A`: The type of agent identifiers that this extractor is compatible with.
+/// - `C`: The type of message payloads that this extractor is compatible with.
+pub trait ChunkedExtractor<A, C>
+where
+    A: ?Sized,
+    C: ?Sized,
+{
+    /// The policy extracted.
+    type Policy: Policy;
+    /// Any errors thrown if the concatenated payload is unparseable.
+    type Error: Error;
+
+
+    /// Extracts the policy from a single (possibly concatenated) payload.
+    ///
+    /// # Arguments
+    /// - `author`: The author of the payload.
+    /// - `payload`: The payload to extract from.
+    ///
+    /// # Returns
+    /// A [`ChunkedExtractor::Policy`] that describes the policy extracted from `payload`.
+    ///
+    /// # Errors
+    /// This function should error if and only if the policy in `payload` fails to parse.
+    fn extract_payload(&self, author: &A, payload: &C) -> Result<Self::Policy, Self::Error>;
+
+    /// Extracts the policy from a set of chunks.
+    ///
+    /// The chunks are ordered by their [`Sequenced::seq()`]-number, and then their payloads are
+    /// [concatenated](Concat) before being given to [`ChunkedExtractor::extract_payload()`]. The
+    /// sequence must start at `0` and be contiguous, and all chunks must be written by the same
+    /// author. Both textual ([`str`]) and binary (`[u8]`) payloads can be concatenated.
+    ///
+    /// If `msgs` is empty, then the [default](Default) policy is returned.
+    ///
+    /// # Arguments
+    /// - `msgs`: A [`Set`] of chunks that we will be extracting from.
+    ///
+    /// # Returns
+    /// A [`ChunkedExtractor::Policy`] that describes the policy extracted from the concatenation
+    /// of `msgs`.
+    ///
+    /// # Errors
+    /// This function errors if the sequence has a duplicate or a missing chunk (i.e., a gap), if
+    /// the chunks have different authors, if we failed to iterate over `msgs` or if the
+    /// concatenated policy failed to parse.
+    fn extract_chunked<M, S>(&self, msgs: &S) -> Result<Self::Policy, ChunkError<S::Error, Self::Error>>
+    where
+        M: Message<AuthorId = A, Payload = C> + Sequenced,
+        S: Set<M>,
+        A: Eq,
+        C: Concat,
+        C::Owned: Default,
+    {
+        // Order the chunks
+        let mut chunks: Vec<&M> = msgs.iter().map_err(ChunkError::Set)?.collect();
+        chunks.sort_by_key(|msg| msg.seq());
+
+        // Concatenate them, checking the sequence and authors as we go
+        let mut author: Option<&A> = None;
+        let mut payload: C::Owned = C::Owned::default();
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let (i, seq): (u64, u64) = (i as u64, chunk.seq());
+            if seq < i {
+                return Err(ChunkError::Duplicate { seq });
+            } else if seq > i {
+                return Err(ChunkError::Gap { missing: i });
+            }
+            match author {
+                Some(author) if author != chunk.author_id() => return Err(ChunkError::MixedAuthors),
+                Some(_) => {},
+                None => author = Some(chunk.author_id()),
+            }
+            chunk.payload().append_to(&mut payload);
+        }
+
+        // Parse the result
+        match author {
+            Some(author) => self.extract_payload(author, payload.borrow()).map_err(ChunkError::Extract),
+            None => Ok(Self::Policy::default()),
+        }
+    }
+}
+
+
+
+/// Defines the encodings in which policy can be written down.
+///
+/// Agents using different encodings can use [`negotiate()`] to find one they both understand.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum PolicyFormat {
+    /// Policy is written in (some dialect of) Datalog.
+    Datalog,
+    /// Policy is written in Slick.
+    Slick,
+    /// Policy is written in some other format, identified by name.
+    Other(String),
+}
+
+/// Finds a policy format that is supported by two agents.
+///
+/// # Arguments
+/// - `local`: The formats supported by the local agent, in order of preference.
+/// - `remote`: The formats supported by the remote agent.
+///
+/// # Returns
+/// The most preferred format (according to `local`) that is also in `remote`, or [`None`] if the
+/// agents have no format in common.
+#[inline]
+pub fn negotiate(local: &[PolicyFormat], remote: &[PolicyFormat]) -> Option<PolicyFormat> { local.iter().find(|format| remote.contains(format)).cloned() }
+
+
+
+/// Collects [`Extractor`]s for different [`PolicyFormat`]s.
+///
+/// This allows a runtime with heterogeneous agents to select the right extractor for every peer.
+///
+/// # Generics
+/// - `E`: The type of [`Extractor`] stored in the registry.
+#[derive(Clone, Debug)]
+pub struct ExtractorRegistry<E> {
+    /// The extractors, in order of registration (and thus preference).
+    extractors: Vec<(PolicyFormat, E)>,
+}
+
+// Constructors
+impl<E> Default for ExtractorRegistry<E> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<E> ExtractorRegistry<E> {
+    /// Constructor for the ExtractorRegistry that initializes it without extractors.
+    ///
+    /// # Returns
+    /// A new ExtractorRegistry, ready to register extractors in.
+    #[inline]
+    pub const fn new() -> Self { Self { extractors: Vec::new() } }
+}
+
+// Registry
+impl<E> ExtractorRegistry<E> {
+    /// Registers a new extractor for a particular format.
+    ///
+    /// Formats are preferred in the order in which they are first registered.
+    ///
+    /// # Arguments
+    /// - `format`: The [`PolicyFormat`] that `extractor` can extract.
+    /// - `extractor`: The extractor to register.
+    ///
+    /// # Returns
+    /// The extractor that was previously registered for `format`, if any.
+    #[inline]
+    pub fn register(&mut self, format: PolicyFormat, extractor: E) -> Option<E> {
+        for (f, e) in &mut self.extractors {
+            if f == &format {
+                return Some(std::mem::replace(e, extractor));
+            }
+        }
+        self.extractors.push((format, extractor));
+        None
+    }
+
+    /// Retrieves the extractor for a particular format.
+    ///
+    /// # Arguments
+    /// - `format`: The [`PolicyFormat`] to find the extractor of.
+    ///
+    /// # Returns
+    /// The registered extractor, or [`None`] if there is none for `format`.
+    #[inline]
+    pub fn get(&self, format: &PolicyFormat) -> Option<&E> { self.extractors.iter().find_map(|(f, e)| if f == format { Some(e) } else { None }) }
+
+    /// Returns the formats supported by this registry.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the registered [`PolicyFormat`]s, in order of preference.
+    #[inline]
+    pub fn formats(&self) -> impl '_ + Iterator<Item = &PolicyFormat> { self.extractors.iter().map(|(f, _)| f) }
+
+    /// Selects the extractor to use for communicating with a particular peer.
+    ///
+    /// This is like [`negotiate()`], but with the formats of this registry as local formats.
+    ///
+    /// # Arguments
+    /// - `remote`: The formats supported by the peer.
+    ///
+    /// # Returns
+    /// The negotiated [`PolicyFormat`] and its extractor, or [`None`] if the peer supports none
+    /// of the registered formats.
+    #[inline]
+    pub fn negotiate(&self, remote: &[PolicyFormat]) -> Option<(&PolicyFormat, &E)> {
+        self.extractors.iter().find_map(|(f, e)| if remote.contains(f) { Some((f, e)) } else { None })
+    }
+}
+
+
+
+/// Describes a single proposal recorded in a [`NegotiationLog`].
+///
+/// # Generics
+/// - `I`: The type of agent identifiers.
+/// - `P`: The type of [`Policy`] proposed.
+#[derive(Clone, Debug)]
+pub struct Proposal<I, P> {
+    /// The agent that proposed the policy.
+    pub proposer: I,
+    /// The round in which the policy was proposed.
+    pub round:    usize,
+    /// The proposed policy snippet.
+    pub policy:   P,
+}
+
+/// Records the proposals and counter-proposals made while agents negotiate an agreement.
+///
+/// This can be used to analyze how (and if) agents reach consensus.
+///
+/// # Generics
+/// - `I`: The type of agent identifiers.
+/// - `P`: The type of [`Policy`] proposed.
+#[derive(Clone, Debug)]
+pub struct NegotiationLog<I, P> {
+    /// The proposals, in order of recording.
+    proposals: Vec<Proposal<I, P>>,
+}
+
+// Constructors
+impl<I, P> Default for NegotiationLog<I, P> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl<I, P> NegotiationLog<I, P> {
+    /// Constructor for the NegotiationLog that initializes it without proposals.
+    ///
+    /// # Returns
+    /// A new NegotiationLog, ready to record proposals in.
+    #[inline]
+    pub const fn new() -> Self { Self { proposals: Vec::new() } }
+}
+
+// Log
+impl<I, P> NegotiationLog<I, P> {
+    /// Records a new proposal.
+    ///
+    /// # Arguments
+    /// - `proposer`: The agent that proposed `policy`.
+    /// - `round`: The round in which `policy` was proposed.
+    /// - `policy`: The proposed policy snippet.
+    #[inline]
+    pub fn propose(&mut self, proposer: I, round: usize, policy: P) { self.proposals.push(Proposal { proposer, round, policy }); }
+
+    /// Returns all recorded proposals.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the [`Proposal`]s, in order of recording.
+    #[inline]
+    pub fn proposals(&self) -> impl '_ + Iterator<Item = &Proposal<I, P>> { self.proposals.iter() }
+
+    /// Returns the proposals made in a particular round.
+    ///
+    /// # Arguments
+    /// - `round`: The round to return the proposals of.
+    ///
+    /// # Returns
+    /// An [`Iterator`] over the [`Proposal`]s made in `round`, in order of recording.
+    #[inline]
+    pub fn round(&self, round: usize) -> impl '_ + Iterator<Item = &Proposal<I, P>> { self.proposals.iter().filter(move |p| p.round == round) }
+
+    /// Returns the most recent round in which anything was proposed.
+    ///
+    /// # Returns
+    /// The highest round recorded, or [`None`] if nothing was proposed yet.
+    #[inline]
+    pub fn latest_round(&self) -> Option<usize> { self.proposals.iter().map(|p| p.round).max() }
+
+    /// Checks whether the negotiation has converged.
+    ///
+    /// This is the case if, in the [latest round](NegotiationLog::latest_round()), every agent
+    /// that ever proposed anything made a proposal, and all of these proposals are
+    /// [equivalent](Policy::equivalent()).
+    ///
+    /// # Returns
+    /// The (first) policy proposed in the latest round if the negotiation converged, or [`None`]
+    /// otherwise.
+    #[inline]
+    pub fn converged(&self) -> Option<&P>
+    where
+        I: Eq,
+        P: Policy,
+        <P::Denotation as Denotation>::Effect: PartialEq,
+    {
+        let latest: usize = self.latest_round()?;
+        let first: &P = &self.round(latest).next()?.policy;
+        if !self.proposals.iter().all(|p| self.round(latest).any(|q| q.proposer == p.proposer)) {
+            return None;
+        }
+        if self.round(latest).all(|p| p.policy.equivalent(first)) { Some(first) } else { None }
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+    use crate::auxillary::Authored;
+    use crate::collections::map::Map;
+
+
+    /// Minimal effect, identified by the fact it effects.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestEffect {
+        affector_id: String,
+        fact:        String,
+    }
+    impl Affectored for TestEffect {
+        type AffectorId = str;
+
+        #[inline]
+        fn affector_id(&self) -> &Self::AffectorId { &self.affector_id }
+    }
+    impl Identifiable for TestEffect {
+        type Id = String;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.fact }
+    }
+    impl Effect for TestEffect {
+        type Fact = String;
+
+        #[inline]
+        fn fact(&self) -> &Self::Fact { &self.fact }
+    }
+
+    /// Minimal denotation that explicitly lists its true and unknowable facts.
+    #[derive(Clone, Debug, Default)]
+    struct TestDenotation {
+        truths:     Vec<String>,
+        unknowable: Vec<String>,
+        effects:    Vec<TestEffect>,
+    }
+    impl TestDenotation {
+        /// Constructor for a TestDenotation without effects.
+        fn new(truths: &[&str], unknowable: &[&str]) -> Self {
+            Self { truths: truths.iter().map(|f| f.to_string()).collect(), unknowable: unknowable.iter().map(|f| f.to_string()).collect(), effects: Vec::new() }
+        }
+    }
+    impl Set<String> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &String) -> Result<Option<&String>, Self::Error> { Ok(<[String]>::iter(&self.truths).find(|f| *f == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s String>, Self::Error>
+        where
+            String: 's,
+        {
+            Ok(<[String]>::iter(&self.truths))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.truths.len()) }
+    }
+    impl Map<TestEffect> for TestDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, id: &String) -> Result<Option<&TestEffect>, Self::Error> { Ok(<[TestEffect]>::iter(&self.effects).find(|e| &e.fact == id)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s TestEffect>, Self::Error>
+        where
+            TestEffect: 's,
+        {
+            Ok(<[TestEffect]>::iter(&self.effects))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.effects.len()) }
+    }
+    impl Denotation for TestDenotation {
+        type Effect = TestEffect;
+        type Fact = String;
+
+        #[inline]
+        fn truth_of(&self, fact: &Self::Fact) -> Option<bool> {
+            if <[String]>::contains(&self.truths, fact) {
+                Some(true)
+            } else if <[String]>::contains(&self.unknowable, fact) {
+                None
+            } else {
+                Some(false)
+            }
+        }
+    }
+
+    /// Minimal policy that is a list of facts, which may be negated by prefixing them with `!`.
+    ///
+    /// It is invalid if any fact is both stated and negated. Such facts are unknowable.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct TestPolicy {
+        facts: Vec<String>,
+    }
+    impl TestPolicy {
+        /// Constructor for a TestPolicy with the given facts.
+        fn new(facts: &[&str]) -> Self { Self { facts: facts.iter().map(|f| f.to_string()).collect() } }
+    }
+    impl Policy for TestPolicy {
+        type Denotation = TestDenotation;
+
+        #[inline]
+        fn is_valid(&self) -> bool {
+            !<[String]>::iter(&self.facts).any(|f| <[String]>::iter(&self.facts).any(|g| g.strip_prefix('!') == Some(f.as_str())))
+        }
+
+        #[inline]
+        fn truths(&self) -> Self::Denotation {
+            let (unknowable, truths): (Vec<&str>, Vec<&str>) = <[String]>::iter(&self.facts)
+                .filter(|f| !f.starts_with('!'))
+                .map(String::as_str)
+                .partition(|f| <[String]>::iter(&self.facts).any(|g| g.strip_prefix('!') == Some(f)));
+            TestDenotation::new(&truths, &unknowable)
+        }
+
+        #[inline]
+        fn compose(&self, other: Self) -> Self {
+            let mut res: Self = self.clone();
+            res.compose_mut(other);
+            res
+        }
+
+        #[inline]
+        fn compose_mut(&mut self, other: Self) { self.facts.extend(other.facts); }
+    }
+
+    /// A rule in a [`RulePolicy`], i.e., `head :- body`. Facts are rules without a body.
+    #[derive(Clone, Debug)]
+    struct Rule {
+        /// The identifier of the message that stated the rule.
+        source: u32,
+        head:   String,
+        body:   Vec<String>,
+    }
+
+    /// Shorthand for creating a [`Rule`] from its textual representation, e.g., `c :- a, b`.
+    fn rule(source: u32, text: &str) -> Rule {
+        let (head, body): (&str, &str) = text.split_once(":-").unwrap_or((text, ""));
+        Rule { source, head: head.trim().into(), body: body.split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from).collect() }
+    }
+
+    /// Minimal Datalog-like policy consisting of (positive) rules.
+    #[derive(Clone, Debug, Default)]
+    struct RulePolicy {
+        rules: Vec<Rule>,
+    }
+    impl Policy for RulePolicy {
+        type Denotation = RuleDenotation;
+
+        #[inline]
+        fn is_valid(&self) -> bool { true }
+
+        #[inline]
+        fn truths(&self) -> Self::Denotation { RuleDenotation::derive(self.rules.clone()) }
+
+        #[inline]
+        fn compose(&self, other: Self) -> Self {
+            let mut res: Self = self.clone();
+            res.compose_mut(other);
+            res
+        }
+
+        #[inline]
+        fn compose_mut(&mut self, other: Self) { self.rules.extend(other.rules); }
+    }
+
+    /// Denotation of a [`RulePolicy`], which keeps its rules around to reason about its truths.
+    #[derive(Clone, Debug)]
+    struct RuleDenotation {
+        rules:  Vec<Rule>,
+        truths: Vec<String>,
+    }
+    impl RuleDenotation {
+        /// Computes the denotation of the given rules.
+        fn derive(rules: Vec<Rule>) -> Self {
+            let mut den: Self = Self { rules, truths: Vec::new() };
+            den.saturate();
+            den
+        }
+
+        /// Applies the rules until nothing new can be derived.
+        fn saturate(&mut self) {
+            loop {
+                let new: Option<String> = <[Rule]>::iter(&self.rules)
+                    .find(|r| !<[String]>::contains(&self.truths, &r.head) && <[String]>::iter(&r.body).all(|f| <[String]>::contains(&self.truths, f)))
+                    .map(|r| r.head.clone());
+                match new {
+                    Some(fact) => self.truths.push(fact),
+                    None => return,
+                }
+            }
+        }
+
+        /// Returns the truths in this denotation, sorted for order-independent comparison.
+        fn sorted(&self) -> Vec<&str> {
+            let mut truths: Vec<&str> = <[String]>::iter(&self.truths).map(String::as_str).collect();
+            truths.sort();
+            truths
+        }
+    }
+    impl Set<String> for RuleDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, elem: &String) -> Result<Option<&String>, Self::Error> { Ok(<[String]>::iter(&self.truths).find(|f| *f == elem)) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s String>, Self::Error>
+        where
+            String: 's,
+        {
+            Ok(<[String]>::iter(&self.truths))
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(self.truths.len()) }
+    }
+    impl Map<TestEffect> for RuleDenotation {
+        type Error = Infallible;
+
+        #[inline]
+        fn get(&self, _id: &String) -> Result<Option<&TestEffect>, Self::Error> { Ok(None) }
+
+        #[inline]
+        fn iter<'s>(&'s self) -> Result<impl 's + Iterator<Item = &'s TestEffect>, Self::Error>
+        where
+            TestEffect: 's,
+        {
+            Ok(std::iter::empty())
+        }
+
+        #[inline]
+        fn len(&self) -> Result<usize, Self::Error> { Ok(0) }
+    }
+    impl Denotation for RuleDenotation {
+        type Effect = TestEffect;
+        type Fact = String;
+
+        #[inline]
+        fn truth_of(&self, fact: &Self::Fact) -> Option<bool> { Some(<[String]>::contains(&self.truths, fact)) }
+
+        #[inline]
+        fn explain(&self, fact: &Self::Fact) -> TruthExplanation<Self::Fact> {
+            if !<[String]>::contains(&self.truths, fact) {
+                return TruthExplanation::Underivable;
+            }
+
+            // Find a rule that derives the fact, preferring it to be given
+            let mut rules = <[Rule]>::iter(&self.rules).filter(|r| &r.head == fact);
+            if rules.clone().any(|r| r.body.is_empty()) {
+                return TruthExplanation::Given;
+            }
+            match rules.find(|r| <[String]>::iter(&r.body).all(|f| <[String]>::contains(&self.truths, f))) {
+                Some(r) => TruthExplanation::Derived { antecedents: r.body.clone() },
+                None => unreachable!("True fact {fact:?} has no derivation"),
+            }
+        }
+    }
+    impl ProvenanceDenotation for RuleDenotation {
+        type MessageId = u32;
+
+        #[inline]
+        fn restrict_to<'a>(&self, msgs: impl Iterator<Item = &'a Self::MessageId>) -> Self
+        where
+            Self::MessageId: 'a,
+        {
+            // Only the derivations that use nothing but rules from the given messages remain
+            let msgs: Vec<&u32> = msgs.collect();
+            Self::derive(<[Rule]>::iter(&self.rules).filter(|r| <[&u32]>::contains(&msgs, &&r.source)).cloned().collect())
+        }
+    }
+    impl IncrementalDenotation for RuleDenotation {
+        #[inline]
+        fn add_fact(&mut self, fact: Self::Fact) {
+            // Derivations are monotonic, so we can continue from the current truths
+            self.rules.push(Rule { source: 0, head: fact, body: Vec::new() });
+            self.saturate();
+        }
+
+        #[inline]
+        fn remove_fact(&mut self, fact: &Self::Fact) {
+            // Retracting may invalidate any derived truth, so these are derived again
+            self.rules.retain(|r| !(r.body.is_empty() && &r.head == fact));
+            self.truths.clear();
+            self.saturate();
+        }
+    }
+
+    /// Minimal message with a (textual or binary) payload, which may be one chunk of a larger policy
+    /// document.
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    struct Chunk<P> {
+        author_id: String,
+        seq:       u64,
+        payload:   P,
+    }
+    impl<P> Authored for Chunk<P> {
+        type AuthorId = str;
+
+        #[inline]
+        fn author_id(&self) -> &Self::AuthorId { &self.author_id }
+    }
+    impl<P> Sequenced for Chunk<P> {
+        #[inline]
+        fn seq(&self) -> u64 { self.seq }
+    }
+    impl Message for Chunk<String> {
+        type Payload = str;
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+    impl Message for Chunk<Vec<u8>> {
+        type Payload = [u8];
+
+        #[inline]
+        fn payload(&self) -> &Self::Payload { &self.payload }
+    }
+
+    /// Shorthand for creating a [`Chunk`].
+    fn chunk<P>(author_id: &str, seq: u64, payload: impl Into<P>) -> Chunk<P> { Chunk { author_id: author_id.into(), seq, payload: payload.into() } }
+
+    /// Extractor that reads a (reassembled) payload as a whitespace-separated list of facts.
+    struct ChunkExtractor;
+    impl ChunkedExtractor<str, str> for ChunkExtractor {
+        type Policy = TestPolicy;
+        type Error = Infallible;
+
+        #[inline]
+        fn extract_payload(&self, _author: &str, payload: &str) -> Result<Self::Policy, Self::Error> {
+            Ok(TestPolicy { facts: payload.split_whitespace().map(String::from).collect() })
+        }
+    }
+    impl ChunkedExtractor<str, [u8]> for ChunkExtractor {
+        type Policy = TestPolicy;
+        type Error = Infallible;
+
+        #[inline]
+        fn extract_payload(&self, author: &str, payload: &[u8]) -> Result<Self::Policy, Self::Error> {
+            <Self as ChunkedExtractor<str, str>>::extract_payload(self, author, std::str::from_utf8(payload).unwrap())
+        }
+    }
+
+    /// Error thrown by the [`FactExtractor`].
+    #[derive(Debug, PartialEq)]
+    struct ParseError(String);
+    impl Display for ParseError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Expected a fact ending in '.', got {:?}", self.0) }
+    }
+    impl Error for ParseError {}
+
+    /// Extractor that reads every message payload as a single fact, terminated by a `.`.
+    struct FactExtractor;
+    impl Extractor<str, str> for FactExtractor {
+        type Policy<'m> = TestPolicy;
+        type Error<'m> = ParseError;
+
+        #[inline]
+        fn extract<'m, M: Message<AuthorId = str, Payload = str>>(&self, msgs: &'m impl Set<M>) -> Result<Self::Policy<'m>, Self::Error<'m>> {
+            let mut policy: TestPolicy = TestPolicy::default();
+            for msg in msgs.iter().unwrap() {
+                match msg.payload().strip_suffix('.') {
+                    Some(fact) => policy.facts.push(fact.into()),
+                    None => return Err(ParseError(msg.payload().into())),
+                }
+            }
+            Ok(policy)
+        }
+    }
+
+    /// Collects the facts yielded by some iterator as [`str`]s.
+    fn facts<'s>(iter: impl Iterator<Item = &'s String>) -> Vec<&'s str> { iter.map(String::as_str).collect() }
+
+
+    #[test]
+    fn test_denotation_set_ops() {
+        // `c` is true in both; `d` is true left but unknowable right; `e` is the other way around
+        let lhs: TestDenotation = TestDenotation::new(&["a", "c", "d"], &["x"]);
+        let rhs: TestDenotation = TestDenotation::new(&["b", "c", "e"], &["d", "x"]);
+
+        assert_eq!(facts(lhs.intersect_truths(&rhs)), vec!["c"]);
+        assert_eq!(facts(rhs.intersect_truths(&lhs)), vec!["c"]);
+
+        assert_eq!(facts(lhs.union_truths(&rhs)), vec!["a", "c", "d", "b", "e"]);
+        assert_eq!(facts(rhs.union_truths(&lhs)), vec!["b", "c", "e", "a", "d"]);
+
+        assert_eq!(facts(lhs.difference_truths(&rhs)), vec!["a", "d"]);
+        assert_eq!(facts(rhs.difference_truths(&lhs)), vec!["b", "e"]);
+    }
+
+    #[test]
+    fn test_denotation_set_ops_self() {
+        let den: TestDenotation = TestDenotation::new(&["a", "b"], &["c"]);
+        assert_eq!(facts(den.intersect_truths(&den)), vec!["a", "b"]);
+        assert_eq!(facts(den.union_truths(&den)), vec!["a", "b"]);
+        assert_eq!(facts(den.difference_truths(&den)), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_extract_lenient() {
+        let msgs: Vec<Chunk<String>> = vec![chunk("amy", 0, "a."), chunk("bob", 0, "garbage"), chunk("cho", 0, "!b.")];
+        assert_eq!(FactExtractor.extract(&msgs), Err(ParseError("garbage".into())));
+
+        // The good messages are still composed, the bad one is reported
+        let (policy, errs): (TestPolicy, Vec<(&Chunk<String>, ParseError)>) = FactExtractor.extract_lenient(&msgs).unwrap();
+        assert_eq!(policy, TestPolicy::new(&["a", "!b"]));
+        assert_eq!(errs, vec![(&msgs[1], ParseError("garbage".into()))]);
+    }
+
+    #[test]
+    fn test_provenance_restrict_to() {
+        // Message 1 states `a`, message 2 states `b`. `c` and `d` need both messages, `e` can be derived from either.
+        let policy: RulePolicy = RulePolicy {
+            rules: vec![rule(1, "a"), rule(1, "c :- a, b"), rule(1, "e :- a"), rule(2, "b"), rule(2, "d :- a"), rule(2, "e :- b")],
+        };
+        let den: RuleDenotation = policy.truths();
+        assert_eq!(den.sorted(), vec!["a", "b", "c", "d", "e"]);
+
+        // Single-source facts only remain if their source does, mixed-provenance ones only if a derivation remains
+        assert_eq!(den.restrict_to([1].iter()).sorted(), vec!["a", "e"]);
+        assert_eq!(den.restrict_to([2].iter()).sorted(), vec!["b", "e"]);
+        assert_eq!(den.restrict_to([1, 2].iter()).sorted(), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(den.restrict_to([3].iter()).sorted(), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_incremental_denotation() {
+        let mut policy: RulePolicy = RulePolicy { rules: vec![rule(1, "a"), rule(1, "c :- a, b"), rule(1, "d :- c"), rule(1, "b :- e")] };
+        let mut den: RuleDenotation = policy.truths();
+        assert_eq!(den.sorted(), vec!["a"]);
+
+        // Every change should give the same truths as recomputing the denotation of the changed policy
+        for (add, fact) in [(true, "b"), (true, "e"), (false, "b"), (true, "b"), (false, "e"), (false, "a"), (false, "z"), (true, "a")] {
+            if add {
+                den.add_fact(fact.into());
+                policy.rules.push(rule(2, fact));
+            } else {
+                den.remove_fact(&fact.into());
+                policy.rules.retain(|r| !(r.body.is_empty() && r.head == fact));
+            }
+            assert_eq!(den.sorted(), policy.truths().sorted(), "{} {fact:?}", if add { "Added" } else { "Removed" });
+        }
+        assert_eq!(den.sorted(), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_denotation_explain() {
+        let den: RuleDenotation = RulePolicy { rules: vec![rule(1, "a"), rule(1, "b"), rule(1, "c :- a, b"), rule(1, "d :- c"), rule(1, "d :- z")] }.truths();
+        assert_eq!(den.explain(&"d".into()), TruthExplanation::Derived { antecedents: vec!["c".into()] });
+        assert_eq!(den.explain(&"c".into()), TruthExplanation::Derived { antecedents: vec!["a".into(), "b".into()] });
+        assert_eq!(den.explain(&"a".into()), TruthExplanation::Given);
+        assert_eq!(den.explain(&"z".into()), TruthExplanation::Underivable);
+
+        // Denotations that don't implement it cannot explain anything
+        let den: TestDenotation = TestDenotation::new(&["a"], &["b"]);
+        assert_eq!(den.explain(&"a".into()), TruthExplanation::Opaque);
+        assert_eq!(den.explain(&"b".into()), TruthExplanation::Opaque);
+        assert_eq!(den.explain(&"c".into()), TruthExplanation::Opaque);
+    }
+
+    #[test]
+    fn test_extract_chunked() {
+        // Chunks are reassembled in order before parsing, so facts may span chunks
+        let chunks: Vec<Chunk<String>> = vec![chunk("amy", 2, "r baz"), chunk("amy", 0, "fo"), chunk("amy", 1, "o ba")];
+        assert_eq!(ChunkExtractor.extract_chunked(&chunks).unwrap(), TestPolicy::new(&["foo", "bar", "baz"]));
+
+        // No chunks give the default policy
+        assert_eq!(ChunkExtractor.extract_chunked(&Vec::<Chunk<String>>::new()).unwrap(), TestPolicy::default());
+    }
+
+    #[test]
+    fn test_extract_chunked_binary() {
+        let chunks: Vec<Chunk<Vec<u8>>> = vec![chunk("amy", 1, b"o ba".as_slice()), chunk("amy", 2, b"r baz".as_slice()), chunk("amy", 0, b"fo".as_slice())];
+        assert_eq!(ChunkExtractor.extract_chunked(&chunks).unwrap(), TestPolicy::new(&["foo", "bar", "baz"]));
+    }
+
+    #[test]
+    fn test_extract_chunked_errors() {
+        let chunks: Vec<Chunk<String>> = vec![chunk("amy", 0, "foo"), chunk("amy", 2, "bar")];
+        assert!(matches!(ChunkExtractor.extract_chunked(&chunks), Err(ChunkError::Gap { missing: 1 })));
+        let chunks: Vec<Chunk<String>> = vec![chunk("amy", 1, "bar"), chunk("amy", 2, "baz")];
+        assert!(matches!(ChunkExtractor.extract_chunked(&chunks), Err(ChunkError::Gap { missing: 0 })));
+
+        let chunks: Vec<Chunk<String>> = vec![chunk("amy", 0, "foo"), chunk("amy", 1, "bar"), chunk("amy", 1, "baz")];
+        assert!(matches!(ChunkExtractor.extract_chunked(&chunks), Err(ChunkError::Duplicate { seq: 1 })));
+
+        let chunks: Vec<Chunk<String>> = vec![chunk("amy", 0, "foo"), chunk("bob", 1, "bar")];
+        assert!(matches!(ChunkExtractor.extract_chunked(&chunks), Err(ChunkError::MixedAuthors)));
+    }
+
+    #[test]
+    fn test_policy_equivalent() {
+        // Composed differently, but the same truths
+        let lhs: TestPolicy = TestPolicy::new(&["a", "b"]).compose(TestPolicy::new(&["c", "!d"]));
+        let rhs: TestPolicy = TestPolicy::new(&["c", "a"]).compose(TestPolicy::new(&["b", "a"]));
+        assert!(lhs.equivalent(&rhs));
+        assert!(rhs.equivalent(&lhs));
+        assert!(lhs.equivalent(&lhs));
+
+        // Different truths
+        let other: TestPolicy = TestPolicy::new(&["a", "b"]);
+        assert!(!lhs.equivalent(&other));
+        assert!(!other.equivalent(&lhs));
+        let other: TestPolicy = TestPolicy::new(&["a", "b", "c", "d"]);
+        assert!(!lhs.equivalent(&other));
+        assert!(!other.equivalent(&lhs));
+    }
+
+    #[test]
+    fn test_policy_equivalent_unknowable() {
+        // `b` is unknowable in one, and true in the other
+        let unknowable: TestPolicy = TestPolicy::new(&["a", "b", "!b"]);
+        assert_eq!(unknowable.truths().truth_of(&"b".into()), None);
+        assert!(!unknowable.equivalent(&TestPolicy::new(&["a", "b"])));
+        assert!(!TestPolicy::new(&["a", "b"]).equivalent(&unknowable));
+
+        // Unknowable in one and false in the other cannot be told apart, as neither yields `b` as a truth
+        assert!(unknowable.equivalent(&TestPolicy::new(&["a"])));
+        assert!(TestPolicy::new(&["a"]).equivalent(&unknowable));
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let other: PolicyFormat = PolicyFormat::Other("eflint".into());
+
+        // The local preference wins
+        assert_eq!(negotiate(&[PolicyFormat::Slick, PolicyFormat::Datalog], &[PolicyFormat::Datalog, PolicyFormat::Slick]), Some(PolicyFormat::Slick));
+        assert_eq!(negotiate(&[PolicyFormat::Datalog, PolicyFormat::Slick], &[PolicyFormat::Slick, PolicyFormat::Datalog]), Some(PolicyFormat::Datalog));
+        assert_eq!(negotiate(&[other.clone(), PolicyFormat::Datalog], &[PolicyFormat::Slick, other.clone()]), Some(other.clone()));
+
+        // No overlap
+        assert_eq!(negotiate(&[PolicyFormat::Datalog, other.clone()], &[PolicyFormat::Slick, PolicyFormat::Other("slick".into())]), None);
+        assert_eq!(negotiate(&[], &[PolicyFormat::Slick]), None);
+        assert_eq!(negotiate(&[PolicyFormat::Slick], &[]), None);
+    }
+
+    #[test]
+    fn test_extractor_registry_negotiate() {
+        let mut registry: ExtractorRegistry<&'static str> = ExtractorRegistry::new();
+        assert_eq!(registry.register(PolicyFormat::Datalog, "datalog"), None);
+        assert_eq!(registry.register(PolicyFormat::Slick, "slick"), None);
+        assert_eq!(registry.register(PolicyFormat::Datalog, "datalog v2"), Some("datalog"));
+        assert_eq!(registry.formats().collect::<Vec<_>>(), vec![&PolicyFormat::Datalog, &PolicyFormat::Slick]);
+        assert_eq!(registry.get(&PolicyFormat::Slick), Some(&"slick"));
+
+        // Returns the extractor of the negotiated format
+        assert_eq!(registry.negotiate(&[PolicyFormat::Slick]), Some((&PolicyFormat::Slick, &"slick")));
+        assert_eq!(registry.negotiate(&[PolicyFormat::Slick, PolicyFormat::Datalog]), Some((&PolicyFormat::Datalog, &"datalog v2")));
+        assert_eq!(registry.negotiate(&[PolicyFormat::Other("datalog".into())]), None);
+    }
+
+    #[test]
+    fn test_negotiation_log_converged() {
+        let mut log: NegotiationLog<&'static str, TestPolicy> = NegotiationLog::new();
+        assert!(log.converged().is_none());
+
+        // Round 1: everyone proposes something different
+        log.propose("amy", 1, TestPolicy::new(&["a"]));
+        log.propose("bob", 1, TestPolicy::new(&["b"]));
+        log.propose("cho", 1, TestPolicy::new(&["a", "!b"]));
+        assert_eq!(log.latest_round(), Some(1));
+        assert!(log.converged().is_none());
+
+        // Round 2: the proposals agree, but not everyone made one
+        log.propose("amy", 2, TestPolicy::new(&["a", "b"]));
+        log.propose("bob", 2, TestPolicy::new(&["b", "a"]));
+        assert_eq!(log.latest_round(), Some(2));
+        assert!(log.converged().is_none());
+
+        // Round 3: everyone agrees (even though written down differently)
+        log.propose("bob", 3, TestPolicy::new(&["b", "a", "a"]));
+        log.propose("cho", 3, TestPolicy::new(&["a"]).compose(TestPolicy::new(&["b"])));
+        log.propose("amy", 3, TestPolicy::new(&["a", "b", "!c"]));
+        assert_eq!(log.latest_round(), Some(3));
+        assert_eq!(log.converged(), Some(&TestPolicy::new(&["b", "a", "a"])));
+        assert_eq!(log.round(3).count(), 3);
+        assert_eq!(log.proposals().count(), 8);
+    }
+
+    #[test]
+    fn test_policy_would_remain_valid() {
+        let policy: TestPolicy = TestPolicy::new(&["a", "!b"]);
+        assert!(policy.is_valid());
+
+        // Compatible snippet
+        assert!(policy.would_remain_valid(&TestPolicy::new(&["c", "!d"])));
+        assert_eq!(policy, TestPolicy::new(&["a", "!b"]));
+
+        // Conflicting snippets (both ways around)
+        assert!(!policy.would_remain_valid(&TestPolicy::new(&["c", "b"])));
+        assert_eq!(policy, TestPolicy::new(&["a", "!b"]));
+        assert!(!policy.would_remain_valid(&TestPolicy::new(&["!a"])));
+        assert_eq!(policy, TestPolicy::new(&["a", "!b"]));
+    }
 }
@@ -4,7 +4,7 @@
 //  Created:
 //    10 Dec 2024, 17:11:17
 //  Last edited:
-//    29 Jan 2025, 22:03:37
+//    15 Oct 2026, 14:32:21
 //  Auto updated?
 //    Yes
 //
@@ -37,18 +37,32 @@ pub trait System {
 
     /// Runs this system for a given set of agents.
     ///
+    /// # Generics
+    /// - `A`: The type of agents to run.
+    /// - `C`: The type of context shared with every agent (see [`Agent`]).
+    ///
     /// # Arguments
     /// - `agents`: Something yielding the total set of [`Agent`]s to run.
     /// - `synchronizer`: The [`Synchronizer`] that can influence synchronized sets and/or when
     ///   the system stops.
+    /// - `context`: Some shared, read-only context that is given to every agent on every poll.
+    ///
+    /// # Shutdown
+    /// Implementations must call [`Agent::on_shutdown()`] exactly once for every agent, after
+    /// polling it for the last time. Agents that return [`Poll::Ready`](std::task::Poll::Ready)
+    /// get their hook right away. When the system stops for any other reason (e.g., the
+    /// synchronizer stopped it or a budget ran out), all remaining agents get theirs. Either way,
+    /// all hooks have been called before this function returns.
     ///
     /// # Errors
     /// This function errors whenever anything in the set goes wrong.
-    fn run<A>(
+    fn run<A, C>(
         &mut self,
         agents: impl IntoIterator<Item = A>,
         synchronizer: impl Synchronizer<Self::Payload, Id = Self::SynchronizerId>,
+        context: &C,
     ) -> Result<(), Self::Error>
     where
-        A: Agent<Self::Payload, Id = Self::AgentId>;
+        A: Agent<Self::Payload, C, Id = Self::AgentId>,
+        C: ?Sized;
 }